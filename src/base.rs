@@ -1,16 +1,55 @@
+use thiserror::Error;
+use crate::image::Image;
+use crate::layout::{BoxConstraints, Context, Flex};
 use crate::math::*;
+use crate::render::Canvas;
 
 pub trait Widget: Element {
 }
 
-pub trait Layer<E: Element>: Element {
+pub trait Layer<E: Element + ?Sized>: Element {
     fn inner(&self) -> &E;
+
+    /// The element must be laid out again after changing the inner one.
+    fn inner_mut(&mut self) -> &mut E;
 }
 
 pub trait Element {
-    fn width(&self) -> f32;
+    /// Compute the size of the element within the constraints given by the parent
+    /// and position the children. Returned size must satisfy the minimum constraints
+    /// and should satisfy the maximum ones. Only elements whose content cannot shrink
+    /// any further may exceed the maximum, in which case the content overflows the
+    /// space given by the parent. The context is passed down to the children
+    /// unless the element changes it for its subtree.
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size;
+
+    /// Distance from the top of the element to its first baseline after the last
+    /// layout, if the element has any text.
+    fn baseline(&self) -> Option<f32> {
+        None
+    }
+
+    /// Whether the point hits the element painted into the given rectangle.
+    /// Both are in the coordinate system of the parent.
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        rect.contains_point(point)
+    }
+
+    /// Space beyond each side of its rectangle the element paints into after
+    /// the last layout, such as for shadows. It takes no part in layout.
+    fn ink_overflow(&self) -> Insets {
+        Insets::zero()
+    }
+
+    /// Share of the free space the element takes in the array it is a direct
+    /// child of, if it is [flexible](crate::layout::Flexible).
+    fn flex(&self) -> Option<Flex> {
+        None
+    }
 
-    fn height(&self) -> f32;
+    /// Paint the element into the given rectangle of the canvas. The rectangle has
+    /// the size returned by the last [layout](Element::layout).
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect);
 }
 
 pub trait Builder {
@@ -19,25 +58,247 @@ pub trait Builder {
     fn build(self) -> Self::Element;
 }
 
-pub trait Composite<E: Element>: Element {
+pub trait Composite<C: Children + ?Sized>: Element {
+    fn children(&self) -> &C;
+
+    /// The element must be laid out again after changing the children.
+    fn children_mut(&mut self) -> &mut C;
+
+    /// Mutable access to the children for changing only the one at the index,
+    /// so that the element laid out again keeps the sizes of the others. The
+    /// children must not be added, removed or reordered through it.
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        let _ = index;
+        self.children_mut()
+    }
+}
+
+/// Element of any type, for the containers with children of different types
+/// chosen at runtime.
+pub type AnyElement = Box<dyn Element>;
+
+impl<E: Element + ?Sized> Element for Box<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        (**self).layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        (**self).baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        (**self).hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        (**self).ink_overflow()
+    }
+
+    fn flex(&self) -> Option<Flex> {
+        (**self).flex()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        (**self).paint(canvas, rect)
+    }
 }
 
+/// Ordered collection of the elements of a composite.
+///
+/// Implemented for vectors and arrays of elements of the same type and for
+/// tuples of elements of different types. Containers go through the methods
+/// taking the index of the element, which these implementations dispatch to
+/// the type of the element statically. Otherwise they default to going
+/// through [`get`](Children::get) and [`get_mut`](Children::get_mut).
+///
+/// Methods taking an index panic if there is no element at the index.
+pub trait Children {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, index: usize) -> Option<&dyn Element>;
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut dyn Element>;
+
+    fn layout_at(&mut self, index: usize, constraints: BoxConstraints, context: &Context) -> Size {
+        self.get_mut(index).unwrap_or_else(|| missing(index)).layout(constraints, context)
+    }
+
+    fn baseline_at(&self, index: usize) -> Option<f32> {
+        self.get(index).unwrap_or_else(|| missing(index)).baseline()
+    }
+
+    fn hit_test_at(&self, index: usize, rect: Rect, point: Point) -> bool {
+        self.get(index).unwrap_or_else(|| missing(index)).hit_test(rect, point)
+    }
+
+    fn ink_overflow_at(&self, index: usize) -> Insets {
+        self.get(index).unwrap_or_else(|| missing(index)).ink_overflow()
+    }
+
+    fn flex_at(&self, index: usize) -> Option<Flex> {
+        self.get(index).unwrap_or_else(|| missing(index)).flex()
+    }
+
+    fn paint_at(&self, index: usize, canvas: &mut dyn Canvas, rect: Rect) {
+        self.get(index).unwrap_or_else(|| missing(index)).paint(canvas, rect)
+    }
+}
+
+fn missing(index: usize) -> ! {
+    panic!("no child at index {index}")
+}
+
+/// Methods of [`Children`] taking an index for a slice of elements of the same type.
+macro_rules! slice_children {
+    () => {
+        fn get(&self, index: usize) -> Option<&dyn Element> {
+            self.as_slice().get(index).map(|e| e as &dyn Element)
+        }
+
+        fn get_mut(&mut self, index: usize) -> Option<&mut dyn Element> {
+            self.as_mut_slice().get_mut(index).map(|e| e as &mut dyn Element)
+        }
+
+        fn layout_at(&mut self, index: usize, constraints: BoxConstraints, context: &Context) -> Size {
+            self.as_mut_slice()[index].layout(constraints, context)
+        }
+
+        fn baseline_at(&self, index: usize) -> Option<f32> {
+            self.as_slice()[index].baseline()
+        }
+
+        fn hit_test_at(&self, index: usize, rect: Rect, point: Point) -> bool {
+            self.as_slice()[index].hit_test(rect, point)
+        }
+
+        fn ink_overflow_at(&self, index: usize) -> Insets {
+            self.as_slice()[index].ink_overflow()
+        }
+
+        fn flex_at(&self, index: usize) -> Option<Flex> {
+            self.as_slice()[index].flex()
+        }
+
+        fn paint_at(&self, index: usize, canvas: &mut dyn Canvas, rect: Rect) {
+            self.as_slice()[index].paint(canvas, rect)
+        }
+    };
+}
+
+impl<E: Element> Children for Vec<E> {
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    slice_children!();
+}
+
+impl<E: Element, const N: usize> Children for [E; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    slice_children!();
+}
+
+macro_rules! tuple_children {
+    ($len:literal; $($name:ident $index:tt),+) => {
+        impl<$($name: Element),+> Children for ($($name,)+) {
+            fn len(&self) -> usize {
+                $len
+            }
+
+            fn get(&self, index: usize) -> Option<&dyn Element> {
+                match index {
+                    $($index => Some(&self.$index),)+
+                    _ => None,
+                }
+            }
+
+            fn get_mut(&mut self, index: usize) -> Option<&mut dyn Element> {
+                match index {
+                    $($index => Some(&mut self.$index),)+
+                    _ => None,
+                }
+            }
+
+            fn layout_at(&mut self, index: usize, constraints: BoxConstraints, context: &Context) -> Size {
+                match index {
+                    $($index => self.$index.layout(constraints, context),)+
+                    _ => missing(index),
+                }
+            }
+
+            fn baseline_at(&self, index: usize) -> Option<f32> {
+                match index {
+                    $($index => self.$index.baseline(),)+
+                    _ => missing(index),
+                }
+            }
+
+            fn hit_test_at(&self, index: usize, rect: Rect, point: Point) -> bool {
+                match index {
+                    $($index => self.$index.hit_test(rect, point),)+
+                    _ => missing(index),
+                }
+            }
+
+            fn ink_overflow_at(&self, index: usize) -> Insets {
+                match index {
+                    $($index => self.$index.ink_overflow(),)+
+                    _ => missing(index),
+                }
+            }
+
+            fn flex_at(&self, index: usize) -> Option<Flex> {
+                match index {
+                    $($index => self.$index.flex(),)+
+                    _ => missing(index),
+                }
+            }
+
+            fn paint_at(&self, index: usize, canvas: &mut dyn Canvas, rect: Rect) {
+                match index {
+                    $($index => self.$index.paint(canvas, rect),)+
+                    _ => missing(index),
+                }
+            }
+        }
+    };
+}
+
+tuple_children!(1; A 0);
+tuple_children!(2; A 0, B 1);
+tuple_children!(3; A 0, B 1, C 2);
+tuple_children!(4; A 0, B 1, C 2, D 3);
+tuple_children!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_children!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+tuple_children!(7; A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple_children!(8; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+tuple_children!(9; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+tuple_children!(10; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+tuple_children!(11; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+tuple_children!(12; A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
 pub struct Blank;
 
 impl Widget for Blank {}
 
 impl Element for Blank {
-    fn width(&self) -> f32 {
-        0.0
+    fn layout(&mut self, constraints: BoxConstraints, _context: &Context) -> Size {
+        constraints.constrain(0.0, 0.0)
     }
 
-    fn height(&self) -> f32 {
-        0.0
-    }
+    fn paint(&self, _canvas: &mut dyn Canvas, _rect: Rect) {}
 }
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Color {
     pub red: PercentUnsigned,
     pub green: PercentUnsigned,
@@ -45,5 +306,196 @@ pub struct Color {
     pub alpha: PercentUnsigned,
 }
 
-#[derive(Clone)]
-pub struct Shader; // TODO
+impl Color {
+    pub const fn new(red: PercentUnsigned, green: PercentUnsigned, blue: PercentUnsigned, alpha: PercentUnsigned) -> Self {
+        Self { red, green, blue, alpha }
+    }
+
+    pub const fn black() -> Self {
+        Self::new(PercentUnsigned::zero(), PercentUnsigned::zero(), PercentUnsigned::zero(), PercentUnsigned::one())
+    }
+
+    pub const fn white() -> Self {
+        Self::new(PercentUnsigned::one(), PercentUnsigned::one(), PercentUnsigned::one(), PercentUnsigned::one())
+    }
+
+    pub const fn transparent() -> Self {
+        Self::new(PercentUnsigned::zero(), PercentUnsigned::zero(), PercentUnsigned::zero(), PercentUnsigned::zero())
+    }
+
+    /// Red, green and blue channels multiplied by alpha, followed by alpha.
+    pub fn premultiplied(&self) -> [f32; 4] {
+        let alpha: f32 = self.alpha.into();
+        [f32::from(self.red) * alpha, f32::from(self.green) * alpha, f32::from(self.blue) * alpha, alpha]
+    }
+
+    /// Color from the premultiplied channels, clamped to the valid range.
+    pub fn from_premultiplied([red, green, blue, alpha]: [f32; 4]) -> Self {
+        let alpha = PercentUnsigned::clamped(alpha);
+        let straight = |channel: f32| match f32::from(alpha) {
+            a if a > 0.0 => PercentUnsigned::clamped(channel / a),
+            _ => PercentUnsigned::zero(),
+        };
+        Self::new(straight(red), straight(green), straight(blue), alpha)
+    }
+}
+
+/// Color at a position along a gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorStop {
+    pub position: PercentUnsigned,
+    pub color: Color,
+}
+
+impl ColorStop {
+    pub const fn new(position: PercentUnsigned, color: Color) -> Self {
+        Self { position, color }
+    }
+}
+
+/// How a gradient continues before its start and past its end.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Spread {
+    /// Colors of the first and last stops extend indefinitely.
+    #[default]
+    Pad,
+
+    /// Gradient starts over.
+    Repeat,
+
+    /// Gradient goes back and forth.
+    Reflect,
+}
+
+/// Colors interpolated between stops, with premultiplied alpha.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "RawGradient"))]
+pub struct Gradient {
+    stops: Vec<ColorStop>,
+    spread: Spread,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum GradientError {
+    #[error("gradient has no color stops")]
+    Empty,
+}
+
+impl Gradient {
+    /// Gradient through the stops sorted by position. Stops at the same
+    /// position make a sharp transition in the order they are given.
+    pub fn new(stops: impl Into<Vec<ColorStop>>) -> Result<Self, GradientError> {
+        let mut stops = stops.into();
+        if stops.is_empty() {
+            return Err(GradientError::Empty);
+        }
+
+        stops.sort_by_key(|stop| stop.position);
+        Ok(Self { stops, spread: Spread::default() })
+    }
+
+    pub fn spread(mut self, spread: Spread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn stops(&self) -> &[ColorStop] {
+        &self.stops
+    }
+
+    pub const fn spread_mode(&self) -> Spread {
+        self.spread
+    }
+
+    /// Color at the offset along the gradient, where zero is the start and one
+    /// is the end of the gradient.
+    pub fn color_at(&self, offset: f32) -> Color {
+        let offset = match self.spread {
+            _ if !offset.is_finite() => 0.0,
+            Spread::Pad => offset.clamp(0.0, 1.0),
+            Spread::Repeat => offset.rem_euclid(1.0),
+            Spread::Reflect => 1.0 - (offset.rem_euclid(2.0) - 1.0).abs(),
+        };
+
+        let next = self.stops.partition_point(|stop| f32::from(stop.position) <= offset);
+        let (before, after) = match next {
+            0 => return self.stops[0].color,
+            n if n == self.stops.len() => return self.stops[n - 1].color,
+            n => (self.stops[n - 1], self.stops[n]),
+        };
+
+        let start: f32 = before.position.into();
+        let t = (offset - start) / (f32::from(after.position) - start);
+        let (from, to) = (before.color.premultiplied(), after.color.premultiplied());
+        Color::from_premultiplied(std::array::from_fn(|i| from[i] + (to[i] - from[i]) * t))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawGradient {
+    stops: Vec<ColorStop>,
+    #[serde(default)]
+    spread: Spread,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawGradient> for Gradient {
+    type Error = GradientError;
+
+    fn try_from(raw: RawGradient) -> Result<Self, Self::Error> {
+        Ok(Self::new(raw.stops)?.spread(raw.spread))
+    }
+}
+
+/// Source of the colors of a filled or stroked shape. Points and angles are in
+/// the coordinate system of the canvas the shape is drawn in.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Shader {
+    Solid(Color),
+
+    /// Gradient along the line from the start to the end, constant across it.
+    Linear {
+        start: Point,
+        end: Point,
+        gradient: Gradient,
+    },
+
+    /// Gradient from the center to the circle of the radius.
+    Radial {
+        center: Point,
+        radius: Positive,
+        gradient: Gradient,
+    },
+
+    /// Gradient sweeping once clockwise around the center, starting at the
+    /// angle in radians from the positive x axis.
+    Conic {
+        center: Point,
+        angle: Number,
+        gradient: Gradient,
+    },
+
+    /// Image repeated in both directions so that one of the copies fills the
+    /// rectangle.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Pattern {
+        image: Image,
+        rect: Rect,
+    },
+}
+
+impl Default for Shader {
+    fn default() -> Self {
+        Self::Solid(Color::black())
+    }
+}
+
+impl From<Color> for Shader {
+    fn from(color: Color) -> Self {
+        Self::Solid(color)
+    }
+}
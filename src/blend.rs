@@ -0,0 +1,246 @@
+use crate::base::*;
+use crate::layout::{BoxConstraints, Context};
+use crate::math::*;
+use crate::render::Canvas;
+
+/// How the colors of a layer are combined with the content below it.
+///
+/// Porter-Duff operators decide which parts of the layer and of the content
+/// below remain. Separable blend modes mix the channels where both overlap and
+/// otherwise behave as [`SourceOver`](Self::SourceOver).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    Clear,
+    Source,
+    Destination,
+    #[default]
+    SourceOver,
+    DestinationOver,
+    SourceIn,
+    DestinationIn,
+    SourceOut,
+    DestinationOut,
+    SourceAtop,
+    DestinationAtop,
+    Xor,
+    Plus,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+}
+
+impl BlendMode {
+    /// Composite the source color onto the destination one, both with
+    /// premultiplied alpha, as defined by the W3C compositing specification.
+    pub fn apply(self, source: [f32; 4], destination: [f32; 4]) -> [f32; 4] {
+        let (source_alpha, destination_alpha) = (source[3], destination[3]);
+        let porter_duff = |a: f32, b: f32| -> [f32; 4] { std::array::from_fn(|i| (source[i] * a + destination[i] * b).min(1.0)) };
+
+        match self {
+            Self::Clear => [0.0; 4],
+            Self::Source => source,
+            Self::Destination => destination,
+            Self::SourceOver => porter_duff(1.0, 1.0 - source_alpha),
+            Self::DestinationOver => porter_duff(1.0 - destination_alpha, 1.0),
+            Self::SourceIn => porter_duff(destination_alpha, 0.0),
+            Self::DestinationIn => porter_duff(0.0, source_alpha),
+            Self::SourceOut => porter_duff(1.0 - destination_alpha, 0.0),
+            Self::DestinationOut => porter_duff(0.0, 1.0 - source_alpha),
+            Self::SourceAtop => porter_duff(destination_alpha, 1.0 - source_alpha),
+            Self::DestinationAtop => porter_duff(1.0 - destination_alpha, source_alpha),
+            Self::Xor => porter_duff(1.0 - destination_alpha, 1.0 - source_alpha),
+            Self::Plus => porter_duff(1.0, 1.0),
+            _ => {
+                let straight = |color: f32, alpha: f32| if alpha > 0.0 { (color / alpha).min(1.0) } else { 0.0 };
+                let mut result = [0.0; 4];
+                for i in 0..3 {
+                    let mixed = self.mix(straight(source[i], source_alpha), straight(destination[i], destination_alpha));
+                    result[i] = source[i] * (1.0 - destination_alpha)
+                        + destination[i] * (1.0 - source_alpha)
+                        + source_alpha * destination_alpha * mixed;
+                }
+                result[3] = source_alpha + destination_alpha - source_alpha * destination_alpha;
+                result
+            }
+        }
+    }
+
+    /// Whether the destination is left untouched where the source is fully
+    /// transparent, so that compositing only affects the painted area.
+    pub fn preserves_destination(self) -> bool {
+        !matches!(self, Self::Clear | Self::Source | Self::SourceIn | Self::DestinationIn | Self::SourceOut | Self::DestinationAtop)
+    }
+
+    /// Blend function of a separable mode for straight color channels.
+    fn mix(self, source: f32, destination: f32) -> f32 {
+        match self {
+            Self::Multiply => source * destination,
+            Self::Screen => source + destination - source * destination,
+            Self::Overlay => Self::HardLight.mix(destination, source),
+            Self::Darken => source.min(destination),
+            Self::Lighten => source.max(destination),
+            Self::ColorDodge => match (source, destination) {
+                (_, d) if d <= 0.0 => 0.0,
+                (s, _) if s >= 1.0 => 1.0,
+                (s, d) => (d / (1.0 - s)).min(1.0),
+            },
+            Self::ColorBurn => match (source, destination) {
+                (_, d) if d >= 1.0 => 1.0,
+                (s, _) if s <= 0.0 => 0.0,
+                (s, d) => 1.0 - ((1.0 - d) / s).min(1.0),
+            },
+            Self::HardLight if source <= 0.5 => Self::Multiply.mix(2.0 * source, destination),
+            Self::HardLight => Self::Screen.mix(2.0 * source - 1.0, destination),
+            Self::SoftLight if source <= 0.5 => destination - (1.0 - 2.0 * source) * destination * (1.0 - destination),
+            Self::SoftLight => {
+                let d = if destination <= 0.25 {
+                    ((16.0 * destination - 12.0) * destination + 4.0) * destination
+                } else {
+                    destination.sqrt()
+                };
+                destination + (2.0 * source - 1.0) * (d - destination)
+            }
+            Self::Difference => (source - destination).abs(),
+            Self::Exclusion => source + destination - 2.0 * source * destination,
+            _ => source,
+        }
+    }
+}
+
+/// Paints the element into a separate layer composited with the opacity, so
+/// that overlapping descendants fade as a whole. The element still receives
+/// hits when fully transparent.
+pub struct Opacity<E: Element> {
+    element: E,
+    opacity: PercentUnsigned,
+}
+
+impl<E: Element> Opacity<E> {
+    pub const fn new(element: E, opacity: PercentUnsigned) -> Self {
+        Self { element, opacity }
+    }
+
+    pub const fn opacity(&self) -> PercentUnsigned {
+        self.opacity
+    }
+
+    /// Changing the opacity does not require a new layout.
+    pub fn set_opacity(&mut self, opacity: PercentUnsigned) {
+        self.opacity = opacity;
+    }
+}
+
+impl<E: Element> Element for Opacity<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        if self.opacity == PercentUnsigned::one() {
+            self.element.paint(canvas, rect);
+        } else if self.opacity > PercentUnsigned::zero() {
+            canvas.push_layer(self.opacity, BlendMode::SourceOver);
+            self.element.paint(canvas, rect);
+            canvas.pop_layer();
+        }
+    }
+}
+
+impl<E: Element> Layer<E> for Opacity<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Paints the element into a separate layer composited with the blend mode
+/// onto the content painted before it. Modes affecting the content where the
+/// layer is empty are kept within the rectangle and the ink overflow of the
+/// element.
+pub struct Blend<E: Element> {
+    element: E,
+    mode: BlendMode,
+}
+
+impl<E: Element> Blend<E> {
+    pub const fn new(element: E, mode: BlendMode) -> Self {
+        Self { element, mode }
+    }
+
+    pub const fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: BlendMode) {
+        self.mode = mode;
+    }
+}
+
+impl<E: Element> Element for Blend<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        if self.mode.preserves_destination() {
+            canvas.push_layer(PercentUnsigned::one(), self.mode);
+            self.element.paint(canvas, rect);
+            canvas.pop_layer();
+            return;
+        }
+
+        // Operators such as Clear affect the content below even where the layer
+        // is empty, so the compositing is kept within the ink of the element.
+        canvas.push_clip(rect + self.element.ink_overflow());
+        canvas.push_layer(PercentUnsigned::one(), self.mode);
+        self.element.paint(canvas, rect);
+        canvas.pop_layer();
+        canvas.pop_clip();
+    }
+}
+
+impl<E: Element> Layer<E> for Blend<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
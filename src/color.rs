@@ -0,0 +1,617 @@
+use std::str::FromStr;
+use thiserror::Error;
+use crate::base::Color;
+use crate::math::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ColorError {
+    #[error("hex color must have 3, 4, 6 or 8 hex digits")]
+    InvalidHex,
+
+    #[error("unknown color name")]
+    UnknownName,
+
+    #[error("malformed color function")]
+    InvalidFunction,
+
+    #[error("color component is not a valid number")]
+    InvalidComponent,
+}
+
+/// Red, green and blue channels without the sRGB transfer function, where light
+/// adds up linearly. Channels outside of zero to one are out of the sRGB gamut.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: PercentUnsigned,
+}
+
+/// Hue in degrees, saturation and lightness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: PercentUnsigned,
+    pub lightness: PercentUnsigned,
+    pub alpha: PercentUnsigned,
+}
+
+/// Hue in degrees, saturation and value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: PercentUnsigned,
+    pub value: PercentUnsigned,
+    pub alpha: PercentUnsigned,
+}
+
+/// Perceptual lightness and two opponent axes, green to red and blue to yellow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+    pub alpha: PercentUnsigned,
+}
+
+/// Polar form of [`Oklab`] with the hue in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub lightness: f32,
+    pub chroma: f32,
+    pub hue: f32,
+    pub alpha: PercentUnsigned,
+}
+
+impl Color {
+    pub fn from_rgba8([red, green, blue, alpha]: [u8; 4]) -> Self {
+        let channel = |value: u8| PercentUnsigned::clamped(value as f32 / 255.0);
+        Self::new(channel(red), channel(green), channel(blue), channel(alpha))
+    }
+
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        let channel = |value: PercentUnsigned| (f32::from(value) * 255.0).round() as u8;
+        [channel(self.red), channel(self.green), channel(self.blue), channel(self.alpha)]
+    }
+
+    pub const fn with_alpha(mut self, alpha: PercentUnsigned) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Increase the HSL lightness by the amount.
+    pub fn lighten(self, amount: PercentUnsigned) -> Self {
+        let mut hsl = Hsl::from(self);
+        hsl.lightness = hsl.lightness.saturating_add(amount);
+        hsl.into()
+    }
+
+    /// Decrease the HSL lightness by the amount.
+    pub fn darken(self, amount: PercentUnsigned) -> Self {
+        let mut hsl = Hsl::from(self);
+        hsl.lightness = hsl.lightness.saturating_sub(amount);
+        hsl.into()
+    }
+
+    /// Interpolate towards the other color with premultiplied alpha, the same
+    /// way as gradients do. The weight of zero gives this color and one the other.
+    pub fn mix(self, other: Self, weight: PercentUnsigned) -> Self {
+        let weight: f32 = weight.into();
+        let (from, to) = (self.premultiplied(), other.premultiplied());
+        Self::from_premultiplied(std::array::from_fn(|i| from[i] + (to[i] - from[i]) * weight))
+    }
+
+    /// Relative luminance as defined by WCAG, ignoring alpha.
+    pub fn luminance(&self) -> f32 {
+        let linear = LinearRgb::from(*self);
+        0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+    }
+
+    /// WCAG contrast ratio between the colors, from 1 for the same luminance to
+    /// 21 for black and white.
+    pub fn contrast_ratio(&self, other: &Self) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+fn to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Hue in degrees from zero inclusive to 360 exclusive.
+fn normalize_hue(hue: f32) -> f32 {
+    let hue = hue.rem_euclid(360.0);
+    if hue.is_finite() && hue < 360.0 {
+        hue
+    } else {
+        0.0
+    }
+}
+
+impl From<Color> for LinearRgb {
+    fn from(color: Color) -> Self {
+        Self {
+            red: to_linear(color.red.into()),
+            green: to_linear(color.green.into()),
+            blue: to_linear(color.blue.into()),
+            alpha: color.alpha,
+        }
+    }
+}
+
+/// Channels out of the sRGB gamut are clamped.
+impl From<LinearRgb> for Color {
+    fn from(linear: LinearRgb) -> Self {
+        let channel = |value: f32| PercentUnsigned::clamped(from_linear(value));
+        Self::new(channel(linear.red), channel(linear.green), channel(linear.blue), linear.alpha)
+    }
+}
+
+/// Hue, the largest and the smallest channel of the color.
+fn hue_max_min(color: Color) -> (f32, f32, f32) {
+    let (red, green, blue) = (f32::from(color.red), f32::from(color.green), f32::from(color.blue));
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == red {
+        60.0 * ((green - blue) / delta)
+    } else if max == green {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+    (normalize_hue(hue), max, min)
+}
+
+/// Color of the hue with the chroma, with the amount added to every channel.
+fn from_hue_chroma(hue: f32, chroma: f32, offset: f32, alpha: PercentUnsigned) -> Color {
+    let sector = normalize_hue(hue) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |value: f32| PercentUnsigned::clamped(value + offset);
+    Color::new(channel(red), channel(green), channel(blue), alpha)
+}
+
+impl From<Color> for Hsl {
+    fn from(color: Color) -> Self {
+        let (hue, max, min) = hue_max_min(color);
+        let lightness = (max + min) / 2.0;
+        let saturation = if max > min { (max - min) / (1.0 - (2.0 * lightness - 1.0).abs()) } else { 0.0 };
+        Self {
+            hue,
+            saturation: PercentUnsigned::clamped(saturation),
+            lightness: PercentUnsigned::clamped(lightness),
+            alpha: color.alpha,
+        }
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Self {
+        let lightness: f32 = hsl.lightness.into();
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * f32::from(hsl.saturation);
+        from_hue_chroma(hsl.hue, chroma, lightness - chroma / 2.0, hsl.alpha)
+    }
+}
+
+impl From<Color> for Hsv {
+    fn from(color: Color) -> Self {
+        let (hue, max, min) = hue_max_min(color);
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        Self {
+            hue,
+            saturation: PercentUnsigned::clamped(saturation),
+            value: PercentUnsigned::clamped(max),
+            alpha: color.alpha,
+        }
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Self {
+        let value: f32 = hsv.value.into();
+        let chroma = value * f32::from(hsv.saturation);
+        from_hue_chroma(hsv.hue, chroma, value - chroma, hsv.alpha)
+    }
+}
+
+impl From<LinearRgb> for Oklab {
+    fn from(linear: LinearRgb) -> Self {
+        let LinearRgb { red, green, blue, alpha } = linear;
+        let l = (0.41222146 * red + 0.53633255 * green + 0.051445995 * blue).cbrt();
+        let m = (0.2119035 * red + 0.6806995 * green + 0.10739696 * blue).cbrt();
+        let s = (0.08830246 * red + 0.28171885 * green + 0.6299787 * blue).cbrt();
+        Self {
+            lightness: 0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            a: 1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            b: 0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+            alpha,
+        }
+    }
+}
+
+impl From<Oklab> for LinearRgb {
+    fn from(lab: Oklab) -> Self {
+        let Oklab { lightness, a, b, alpha } = lab;
+        let l = (lightness + 0.39633778 * a + 0.21580376 * b).powi(3);
+        let m = (lightness - 0.105561346 * a - 0.06385417 * b).powi(3);
+        let s = (lightness - 0.08948418 * a - 1.2914855 * b).powi(3);
+        Self {
+            red: 4.0767417 * l - 3.3077116 * m + 0.23096994 * s,
+            green: -1.268438 * l + 2.6097574 * m - 0.34131938 * s,
+            blue: -0.0041960863 * l - 0.7034186 * m + 1.7076147 * s,
+            alpha,
+        }
+    }
+}
+
+impl From<Color> for Oklab {
+    fn from(color: Color) -> Self {
+        LinearRgb::from(color).into()
+    }
+}
+
+/// Colors out of the sRGB gamut are clamped channel by channel.
+impl From<Oklab> for Color {
+    fn from(lab: Oklab) -> Self {
+        LinearRgb::from(lab).into()
+    }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(lab: Oklab) -> Self {
+        Self {
+            lightness: lab.lightness,
+            chroma: lab.a.hypot(lab.b),
+            hue: normalize_hue(lab.b.atan2(lab.a).to_degrees()),
+            alpha: lab.alpha,
+        }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(lch: Oklch) -> Self {
+        let (sin, cos) = lch.hue.to_radians().sin_cos();
+        Self {
+            lightness: lch.lightness,
+            a: lch.chroma * cos,
+            b: lch.chroma * sin,
+            alpha: lch.alpha,
+        }
+    }
+}
+
+impl From<Color> for Oklch {
+    fn from(color: Color) -> Self {
+        Oklab::from(color).into()
+    }
+}
+
+/// Colors out of the sRGB gamut are clamped channel by channel.
+impl From<Oklch> for Color {
+    fn from(lch: Oklch) -> Self {
+        Oklab::from(lch).into()
+    }
+}
+
+/// Hexadecimal notation, `#rrggbb` for opaque colors and `#rrggbbaa` otherwise.
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [red, green, blue, alpha] = self.to_rgba8();
+        write!(f, "#{red:02x}{green:02x}{blue:02x}")?;
+        if alpha < 255 {
+            write!(f, "{alpha:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses hex notation, CSS named colors and the CSS `rgb()`, `rgba()`, `hsl()`,
+/// `hsla()` and `oklch()` functions, with components separated by commas or
+/// spaces and alpha optionally after a slash. Channels out of range are clamped
+/// as in CSS.
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(hex) = value.strip_prefix('#') {
+            return parse_hex(hex);
+        }
+
+        let Some((name, arguments)) = value.split_once('(') else {
+            return named(&value).ok_or(ColorError::UnknownName);
+        };
+        let arguments = arguments.strip_suffix(')').ok_or(ColorError::InvalidFunction)?;
+        let components: Vec<&str> = arguments
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|component| !component.is_empty())
+            .collect();
+        let (components, alpha) = match components.as_slice() {
+            [a, b, c] => ([*a, *b, *c], PercentUnsigned::one()),
+            [a, b, c, alpha] => ([*a, *b, *c], parse_alpha(alpha)?),
+            _ => return Err(ColorError::InvalidFunction),
+        };
+
+        match name.trim() {
+            "rgb" | "rgba" => {
+                let [red, green, blue] = components.map(parse_channel);
+                Ok(Self::new(red?, green?, blue?, alpha))
+            }
+            "hsl" | "hsla" => Ok(Hsl {
+                hue: parse_hue(components[0])?,
+                saturation: parse_percent(components[1])?,
+                lightness: parse_percent(components[2])?,
+                alpha,
+            }
+            .into()),
+            "oklch" => {
+                let lightness = match components[0].strip_suffix('%') {
+                    Some(percent) => parse_number(percent)? / 100.0,
+                    None => parse_number(components[0])?,
+                };
+                // Chroma of 100% is 0.4 as in CSS.
+                let chroma = match components[1].strip_suffix('%') {
+                    Some(percent) => parse_number(percent)? / 100.0 * 0.4,
+                    None => parse_number(components[1])?,
+                };
+                Ok(Oklch {
+                    lightness: lightness.clamp(0.0, 1.0),
+                    chroma: chroma.max(0.0),
+                    hue: parse_hue(components[2])?,
+                    alpha,
+                }
+                .into())
+            }
+            _ => Err(ColorError::InvalidFunction),
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<Color, ColorError> {
+    let digit = |index: usize| {
+        let digit = hex.as_bytes().get(index).copied().ok_or(ColorError::InvalidHex)?;
+        (digit as char).to_digit(16).map(|digit| digit as u8).ok_or(ColorError::InvalidHex)
+    };
+
+    let mut rgba = [255; 4];
+    match hex.len() {
+        3 | 4 => {
+            for (i, channel) in rgba.iter_mut().enumerate().take(hex.len()) {
+                *channel = digit(i)? * 17;
+            }
+        }
+        6 | 8 => {
+            for (i, channel) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+                *channel = digit(2 * i)? * 16 + digit(2 * i + 1)?;
+            }
+        }
+        _ => return Err(ColorError::InvalidHex),
+    }
+    Ok(Color::from_rgba8(rgba))
+}
+
+fn parse_number(value: &str) -> Result<f32, ColorError> {
+    value.parse::<f32>().ok().filter(|number| number.is_finite()).ok_or(ColorError::InvalidComponent)
+}
+
+/// Percentage, or a number out of 255.
+fn parse_channel(value: &str) -> Result<PercentUnsigned, ColorError> {
+    match value.strip_suffix('%') {
+        Some(percent) => Ok(PercentUnsigned::clamped(parse_number(percent)? / 100.0)),
+        None => Ok(PercentUnsigned::clamped(parse_number(value)? / 255.0)),
+    }
+}
+
+/// Percentage with an optional percent sign.
+fn parse_percent(value: &str) -> Result<PercentUnsigned, ColorError> {
+    let value = value.strip_suffix('%').unwrap_or(value);
+    Ok(PercentUnsigned::clamped(parse_number(value)? / 100.0))
+}
+
+/// Percentage, or a number out of one.
+fn parse_alpha(value: &str) -> Result<PercentUnsigned, ColorError> {
+    match value.strip_suffix('%') {
+        Some(percent) => Ok(PercentUnsigned::clamped(parse_number(percent)? / 100.0)),
+        None => Ok(PercentUnsigned::clamped(parse_number(value)?)),
+    }
+}
+
+/// Angle in degrees unless given in `rad`, `grad` or `turn`.
+fn parse_hue(value: &str) -> Result<f32, ColorError> {
+    let degrees = if let Some(gradians) = value.strip_suffix("grad") {
+        parse_number(gradians)? * 0.9
+    } else if let Some(radians) = value.strip_suffix("rad") {
+        parse_number(radians)?.to_degrees()
+    } else if let Some(turns) = value.strip_suffix("turn") {
+        parse_number(turns)? * 360.0
+    } else {
+        parse_number(value.strip_suffix("deg").unwrap_or(value))?
+    };
+    Ok(normalize_hue(degrees))
+}
+
+fn named(name: &str) -> Option<Color> {
+    if name == "transparent" {
+        return Some(Color::transparent());
+    }
+
+    let (_, rgb) = NAMED.iter().find(|(named, _)| *named == name)?;
+    let [_, red, green, blue] = rgb.to_be_bytes();
+    Some(Color::from_rgba8([red, green, blue, 255]))
+}
+
+/// CSS named colors.
+const NAMED: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use crate::base::*;
+use crate::layout::{BoxConstraints, Context};
+use crate::math::*;
+use crate::render::Canvas;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Bitmap of straight (not premultiplied) RGBA pixels, 8 bits per channel,
+/// stored row by row.
+///
+/// Clones share the pixel buffer and are treated by the renderers as the same image,
+/// which allows backends to cache uploaded textures.
+#[derive(Debug, Clone)]
+pub struct Image {
+    id: u64,
+    width: u32,
+    height: u32,
+    pixels: Arc<[u8]>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ImageError {
+    #[error("image cannot be empty")]
+    Empty,
+
+    #[error("pixel buffer size does not match image dimensions")]
+    SizeMismatch,
+}
+
+impl PartialEq for Image {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Image {}
+
+impl Image {
+    pub fn from_rgba(width: u32, height: u32, pixels: impl Into<Arc<[u8]>>) -> Result<Self, ImageError> {
+        let pixels = pixels.into();
+        if width == 0 || height == 0 {
+            return Err(ImageError::Empty);
+        }
+
+        if pixels.len() != width as usize * height as usize * 4 {
+            return Err(ImageError::SizeMismatch);
+        }
+
+        Ok(Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Identifier shared by all clones of the image.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn pixel_width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn pixel_height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
+
+impl Element for Image {
+    fn layout(&mut self, constraints: BoxConstraints, _context: &Context) -> Size {
+        constraints.constrain(self.width as f32, self.height as f32)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        canvas.draw_image(self, rect);
+    }
+}
+
+impl Widget for Image {}
@@ -1,309 +1,2488 @@
+use std::sync::Arc;
 use thiserror::Error;
 use crate::base::*;
+use crate::dynamic::{Alive, Update};
 use crate::math::*;
+use crate::render::Canvas;
+use crate::text::Style;
 
-pub struct Row<E: Element> {
-    elements: Vec<E>,
+/// Range of sizes the parent allows its child to take.
+///
+/// Minimums are always finite while maximums may be infinite which means the
+/// parent does not limit the child along that axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxConstraints {
+    min_width: f32,
+    max_width: f32,
+    min_height: f32,
+    max_height: f32,
 }
 
-impl<E: Element> Row<E> {
-    pub fn new(elements: Vec<E>) -> Self {
-        Self { elements }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum BoxConstraintsError {
+    #[error("constraint is not a number")]
+    NotANumber,
+
+    #[error("constraint is negative")]
+    Negative,
+
+    #[error("minimum constraint is infinite")]
+    InfiniteMinimum,
+
+    #[error("minimum constraint is greater than maximum")]
+    MinimumExceedsMaximum,
+}
+
+impl BoxConstraints {
+    pub fn new(min_width: f32, max_width: f32, min_height: f32, max_height: f32) -> Result<Self, BoxConstraintsError> {
+        let all = [min_width, max_width, min_height, max_height];
+        if all.iter().any(|v| v.is_nan()) {
+            Err(BoxConstraintsError::NotANumber)
+        } else if all.iter().any(|v| v.is_sign_negative()) {
+            Err(BoxConstraintsError::Negative)
+        } else if min_width.is_infinite() || min_height.is_infinite() {
+            Err(BoxConstraintsError::InfiniteMinimum)
+        } else if min_width > max_width || min_height > max_height {
+            Err(BoxConstraintsError::MinimumExceedsMaximum)
+        } else {
+            Ok(Self { min_width, max_width, min_height, max_height })
+        }
+    }
+
+    /// Constraints allowing only the given size.
+    pub fn tight(size: Size) -> Self {
+        Self {
+            min_width: size.widthf(),
+            max_width: size.widthf(),
+            min_height: size.heightf(),
+            max_height: size.heightf(),
+        }
+    }
+
+    /// Constraints allowing any size up to the given one.
+    pub fn loose(size: Size) -> Self {
+        Self {
+            min_width: 0.0,
+            max_width: size.widthf(),
+            min_height: 0.0,
+            max_height: size.heightf(),
+        }
+    }
+
+    /// Constraints allowing any size at all.
+    pub const fn unbounded() -> Self {
+        Self {
+            min_width: 0.0,
+            max_width: f32::INFINITY,
+            min_height: 0.0,
+            max_height: f32::INFINITY,
+        }
+    }
+
+    pub const fn min_width(&self) -> f32 {
+        self.min_width
+    }
+
+    pub const fn max_width(&self) -> f32 {
+        self.max_width
+    }
+
+    pub const fn min_height(&self) -> f32 {
+        self.min_height
+    }
+
+    pub const fn max_height(&self) -> f32 {
+        self.max_height
+    }
+
+    pub fn has_bounded_width(&self) -> bool {
+        self.max_width.is_finite()
+    }
+
+    pub fn has_bounded_height(&self) -> bool {
+        self.max_height.is_finite()
+    }
+
+    /// Same maximums but no minimums.
+    pub fn loosen(&self) -> Self {
+        Self {
+            min_width: 0.0,
+            min_height: 0.0,
+            ..*self
+        }
+    }
+
+    /// Constraints for the content when the given amount of space is taken
+    /// around it along each axis.
+    pub fn deflate(&self, horizontal: f32, vertical: f32) -> Self {
+        let min_width = (self.min_width - horizontal).max(0.0);
+        let min_height = (self.min_height - vertical).max(0.0);
+        Self {
+            min_width,
+            max_width: (self.max_width - horizontal).max(min_width),
+            min_height,
+            max_height: (self.max_height - vertical).max(min_height),
+        }
+    }
+
+    /// Same height constraints with the width limited only from below.
+    pub fn unbound_width(&self) -> Self {
+        Self {
+            max_width: f32::INFINITY,
+            ..*self
+        }
+    }
+
+    /// Same width constraints with the height limited only from below.
+    pub fn unbound_height(&self) -> Self {
+        Self {
+            max_height: f32::INFINITY,
+            ..*self
+        }
+    }
+
+    /// Constraints satisfying these ones which are the closest to the other ones.
+    pub fn enforce(&self, other: BoxConstraints) -> Self {
+        Self {
+            min_width: other.min_width.clamp(self.min_width, self.max_width),
+            max_width: other.max_width.clamp(self.min_width, self.max_width),
+            min_height: other.min_height.clamp(self.min_height, self.max_height),
+            max_height: other.max_height.clamp(self.min_height, self.max_height),
+        }
+    }
+
+    /// Same constraints with the given extents made tight as far as the
+    /// constraints allow.
+    pub fn tighten(&self, width: Option<f32>, height: Option<f32>) -> Self {
+        let mut tightened = *self;
+        if let Some(width) = width {
+            let width = width.clamp(self.min_width, self.max_width);
+            tightened.min_width = width;
+            tightened.max_width = width;
+        }
+        if let Some(height) = height {
+            let height = height.clamp(self.min_height, self.max_height);
+            tightened.min_height = height;
+            tightened.max_height = height;
+        }
+        tightened
+    }
+
+    /// The size closest to the given one that satisfies the constraints.
+    pub fn constrain(&self, width: f32, height: f32) -> Size {
+        let width = finite_or(width.clamp(self.min_width, self.max_width), self.min_width);
+        let height = finite_or(height.clamp(self.min_height, self.max_height), self.min_height);
+        Size::try_from((width, height)).expect("clamped to valid constraints")
+    }
+
+    /// The size closest to the given one that satisfies the minimum constraints,
+    /// for the content that cannot shrink and has to overflow.
+    pub fn constrain_min(&self, width: f32, height: f32) -> Size {
+        let width = finite_or(width.max(self.min_width), self.min_width);
+        let height = finite_or(height.max(self.min_height), self.min_height);
+        Size::try_from((width, height)).expect("clamped to valid constraints")
+    }
+}
+
+fn finite_or(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        fallback
+    }
+}
+
+/// Direction in which the text and the elements along the horizontal axis flow.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    LeftToRight,
+    RightToLeft,
+}
+
+/// Properties inherited by every element from its ancestors during layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Context {
+    direction: Direction,
+    scale: Positive,
+    text_style: Arc<Style>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self::new(Direction::default())
+    }
+}
+
+impl Context {
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            scale: Positive::one(),
+            text_style: Arc::new(Style::default()),
+        }
+    }
+
+    pub const fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn is_right_to_left(&self) -> bool {
+        self.direction == Direction::RightToLeft
+    }
+
+    pub fn with_direction(&self, direction: Direction) -> Self {
+        let mut context = self.clone();
+        context.direction = direction;
+        context
+    }
+
+    /// Number of device pixels per logical pixel of the window.
+    pub const fn scale(&self) -> Positive {
+        self.scale
+    }
+
+    pub fn with_scale(&self, scale: Positive) -> Self {
+        let mut context = self.clone();
+        context.scale = scale;
+        context
+    }
+
+    /// Style of the text set by the closest [`TextStyle`] ancestor, whose size
+    /// [`Length::Em`] is relative to.
+    pub fn text_style(&self) -> &Style {
+        &self.text_style
+    }
+
+    pub fn with_text_style(&self, text_style: Style) -> Self {
+        let mut context = self.clone();
+        context.text_style = Arc::new(text_style);
+        context
+    }
+}
+
+/// Distance in one of the units resolved within the context during layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Length {
+    /// Logical pixels, the unit everything is laid out in.
+    Px(Unsigned),
+
+    /// Device pixels, as many of them make a logical pixel as the scale factor
+    /// of the window.
+    Dp(Unsigned),
+
+    /// Multiple of the size of the text style of the context.
+    Em(Unsigned),
+
+    /// Share of the space given by the parent, zero if the parent does not
+    /// limit it.
+    Percent(PercentUnsigned),
+}
+
+impl Default for Length {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<Unsigned> for Length {
+    fn from(value: Unsigned) -> Self {
+        Self::Px(value)
+    }
+}
+
+impl Length {
+    pub const fn zero() -> Self {
+        Self::Px(Unsigned::zero())
+    }
+
+    /// Length in logical pixels within the context, where percents are taken
+    /// of the `base`.
+    pub fn resolve(&self, context: &Context, base: f32) -> Unsigned {
+        match *self {
+            Self::Px(value) => value,
+            Self::Dp(value) => value / context.scale(),
+            Self::Em(value) => value * context.text_style().size,
+            Self::Percent(percent) if base.is_finite() => Unsigned::clamped(base * f32::from(percent)),
+            Self::Percent(_) => Unsigned::zero(),
+        }
+    }
+
+    pub fn resolvef(&self, context: &Context, base: f32) -> f32 {
+        self.resolve(context, base).into()
+    }
+}
+
+/// Distribution of the free space along the main axis of an array.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Justify {
+    /// Elements are packed at the start, the container shrinks to fit them.
+    #[default]
+    Start,
+    End,
+    Center,
+
+    /// Free space is put between the elements, none before the first and after the last one.
+    SpaceBetween,
+
+    /// Free space is put around each element so that the gaps between them
+    /// are twice as big as the gaps at the ends.
+    SpaceAround,
+
+    /// Free space is split evenly between the elements and the ends.
+    SpaceEvenly,
+}
+
+impl Justify {
+    /// Space before the first of `count` elements and between each two of them.
+    fn distribute(self, free: f32, count: usize) -> (f32, f32) {
+        let count = count as f32;
+        match self {
+            _ if free <= 0.0 => (0.0, 0.0),
+            Justify::Start => (0.0, 0.0),
+            Justify::End => (free, 0.0),
+            Justify::Center => (free / 2.0, 0.0),
+            Justify::SpaceBetween if count > 1.0 => (0.0, free / (count - 1.0)),
+            Justify::SpaceBetween => (0.0, 0.0),
+            Justify::SpaceAround => (free / count / 2.0, free / count),
+            Justify::SpaceEvenly => (free / (count + 1.0), free / (count + 1.0)),
+        }
+    }
+}
+
+/// Placement of the elements across the main axis of an array.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossAlign {
+    #[default]
+    Start,
+    End,
+    Center,
+
+    /// Elements are forced to take the whole cross extent of the array.
+    Stretch,
+
+    /// Elements are aligned on their first baselines. Only applies to
+    /// horizontal arrays, vertical ones align the elements at the start.
+    /// Elements that report no baseline use their bottom edge.
+    Baseline,
+}
+
+/// Flip the frames horizontally within the given width for right-to-left layout.
+fn mirror<'a>(frames: impl IntoIterator<Item = &'a mut Rect>, width: f32) {
+    for frame in frames {
+        frame.x = width - frame.right();
+    }
+}
+
+/// Whether the point hits any of the elements painted into their frames.
+fn hit_any<C: Children + ?Sized>(children: &C, frames: &[Rect], rect: Rect, point: Point) -> bool {
+    frames
+        .iter()
+        .enumerate()
+        .any(|(i, frame)| children.hit_test_at(i, frame.offset(rect.x, rect.y), point))
+}
+
+/// Space beyond the rectangle of the given size the elements painted into their
+/// frames paint into.
+fn children_overflow<C: Children + ?Sized>(children: &C, frames: &[Rect], size: Size) -> Insets {
+    let bounds = Rect::sized(0.0, 0.0, size);
+    let ink = frames
+        .iter()
+        .enumerate()
+        .fold(bounds, |ink, (i, frame)| ink.union(&(*frame + children.ink_overflow_at(i))));
+    bounds.overflow(&ink)
+}
+
+/// Baseline of the first element having one, positioned in its frame.
+fn first_baseline<C: Children + ?Sized>(children: &C, frames: &[Rect]) -> Option<f32> {
+    frames
+        .iter()
+        .enumerate()
+        .find_map(|(i, frame)| children.baseline_at(i).map(|baseline| frame.y + baseline))
+}
+
+/// Number of the different constraints the size of a child is kept for, the
+/// ones least recently asked for are forgotten first. Enough to keep the sizes
+/// from all the passes of a container for two of its own constraints.
+const MEASUREMENTS: usize = 6;
+
+/// Sizes the children of a container took with the constraints they were laid
+/// out with, so that neither the passes of the container nor its later layouts
+/// lay out an unchanged child again for the same constraints.
+///
+/// A child keeps the layout it was last laid out with, so the children asked
+/// for a size they were not last laid out with are laid out again when the
+/// container [settles](Measurements::settle) before reading their baselines
+/// and at the end of its layout.
+#[derive(Default)]
+struct Measurements {
+    context: Option<Context>,
+    children: Vec<Measured>,
+}
+
+#[derive(Default)]
+struct Measured {
+    sizes: Vec<(BoxConstraints, Size)>,
+    current: Option<BoxConstraints>,
+    requested: Option<BoxConstraints>,
+}
+
+impl Measurements {
+    /// Prepare for the layout of the given number of children, forgetting the
+    /// sizes taken within another context.
+    fn begin(&mut self, len: usize, context: &Context) {
+        if self.context.as_ref() != Some(context) {
+            self.children.clear();
+            self.context = Some(context.clone());
+        }
+        self.children.resize_with(len, Measured::default);
+    }
+
+    fn layout<C: Children + ?Sized>(&mut self, elements: &mut C, index: usize, constraints: BoxConstraints, context: &Context) -> Size {
+        let measured = &mut self.children[index];
+        measured.requested = Some(constraints);
+        if let Some(position) = measured.sizes.iter().position(|(c, _)| *c == constraints) {
+            let entry = measured.sizes.remove(position);
+            measured.sizes.push(entry);
+            return entry.1;
+        }
+
+        let size = elements.layout_at(index, constraints, context);
+        if measured.sizes.len() == MEASUREMENTS {
+            measured.sizes.remove(0);
+        }
+        measured.sizes.push((constraints, size));
+        measured.current = Some(constraints);
+        size
+    }
+
+    /// Lay out again the children last laid out with other constraints than
+    /// they were last asked for.
+    fn settle<C: Children + ?Sized>(&mut self, elements: &mut C, context: &Context) {
+        for (i, measured) in self.children.iter_mut().enumerate() {
+            if let Some(requested) = measured.requested.take() {
+                if measured.current != Some(requested) {
+                    elements.layout_at(i, requested, context);
+                    measured.current = Some(requested);
+                }
+            }
+        }
+    }
+
+    /// Forget all the sizes, for when the children may have changed.
+    fn invalidate(&mut self) {
+        self.children.clear();
+    }
+
+    /// Forget the sizes of the child at the index, for when only it may have changed.
+    fn invalidate_at(&mut self, index: usize) {
+        if let Some(measured) = self.children.get_mut(index) {
+            *measured = Measured::default();
+        }
+    }
+}
+
+/// Layout of elements along an axis, shared by the array containers.
+struct Line {
+    axis: Axis,
+    justify: Justify,
+    cross_align: CrossAlign,
+    overlap: OverlapPolicy,
+    frames: Vec<Rect>,
+    clips: Vec<Option<Rect>>,
+    size: Size,
+    measurements: Measurements,
+}
+
+impl Line {
+    fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            justify: Justify::default(),
+            cross_align: CrossAlign::default(),
+            overlap: OverlapPolicy::default(),
+            frames: Vec::new(),
+            clips: Vec::new(),
+            size: Size::zero(),
+            measurements: Measurements::default(),
+        }
+    }
+
+    fn layout<C: Children + ?Sized>(&mut self, elements: &mut C, constraints: BoxConstraints, context: &Context) -> Size {
+        let (min_main, min_cross) = self.axis.axes(constraints.min_width(), constraints.min_height());
+        let (max_main, max_cross) = self.axis.axes(constraints.max_width(), constraints.max_height());
+        let stretch = self.cross_align == CrossAlign::Stretch && max_cross.is_finite();
+        let min_child_cross = if stretch { max_cross } else { 0.0 };
+
+        self.measurements.begin(elements.len(), context);
+
+        // Rigid elements take their space first, flexible ones share what is left.
+        let flex: Vec<Option<Flex>> = (0..elements.len()).map(|i| elements.flex_at(i)).collect();
+        let mut sizes = vec![(0.0, 0.0); elements.len()];
+        let mut used = 0.0;
+        let mut total_flex = 0.0;
+        for (i, size) in sizes.iter_mut().enumerate() {
+            match flex[i] {
+                Some(flex) if max_main.is_finite() => total_flex += f32::from(flex),
+                _ => {
+                    let s = self.measurements.layout(elements, i, self.axis.constraints(0.0, f32::INFINITY, min_child_cross, max_cross), context);
+                    *size = self.axis.axes(s.widthf(), s.heightf());
+                    used += size.0;
+                }
+            }
+        }
+
+        let free = (max_main - used).max(0.0);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if let Some(flex) = flex[i].filter(|_| max_main.is_finite()) {
+                let extent = free * f32::from(flex) / total_flex;
+                let s = self.measurements.layout(elements, i, self.axis.constraints(extent, extent, min_child_cross, max_cross), context);
+                *size = self.axis.axes(s.widthf(), s.heightf());
+                used += size.0;
+            }
+        }
+
+        // Elements that do not fit get slots shrunk proportionally to their sizes
+        // and are laid out again to let them adapt, those which cannot shrink are
+        // centered in their slots and overlap the neighbours.
+        let mut slots: Vec<f32> = sizes.iter().map(|size| size.0).collect();
+        let overflow = max_main.is_finite() && used > max_main;
+        if overflow {
+            let ratio = max_main / used;
+            for (i, (size, slot)) in sizes.iter_mut().zip(&mut slots).enumerate() {
+                *slot *= ratio;
+                let s = self.measurements.layout(elements, i, self.axis.constraints(0.0, *slot, min_child_cross, max_cross), context);
+                *size = self.axis.axes(s.widthf(), s.heightf());
+            }
+            used = max_main;
+        }
+
+        self.measurements.settle(elements, context);
+        let baseline = self.cross_align == CrossAlign::Baseline && self.axis == Axis::Horizontal;
+        let baselines: Vec<f32> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, size)| elements.baseline_at(i).unwrap_or(size.1))
+            .collect();
+        let above = baselines.iter().fold(0.0f32, |a, b| a.max(*b));
+
+        let mut cross = sizes.iter().fold(0.0f32, |a, size| a.max(size.1));
+        if baseline {
+            let below = sizes.iter().zip(&baselines).fold(0.0f32, |a, (size, b)| a.max(size.1 - b));
+            cross = above + below;
+        }
+        if stretch {
+            cross = max_cross;
+        }
+
+        let fill = max_main.is_finite() && (total_flex > 0.0 || self.justify != Justify::Start);
+        let main = if fill { max_main } else { used };
+        let main = main.clamp(min_main, max_main.max(min_main));
+        let cross = cross.clamp(min_cross, max_cross.max(min_cross));
+
+        // Stretching within unbounded cross extent is only known after measuring all elements.
+        if self.cross_align == CrossAlign::Stretch && !stretch {
+            for (i, size) in sizes.iter_mut().enumerate() {
+                if size.1 < cross {
+                    let s = self.measurements.layout(elements, i, self.axis.constraints(size.0, size.0, cross, cross), context);
+                    *size = self.axis.axes(s.widthf(), s.heightf());
+                }
+            }
+        }
+
+        self.measurements.settle(elements, context);
+
+        let free = main - used;
+        let (leading, between) = self.justify.distribute(free, elements.len());
+
+        self.frames.clear();
+        self.clips.clear();
+        let mut position = leading;
+        for ((size, slot), element_baseline) in sizes.iter().zip(&slots).zip(&baselines) {
+            let offset = match self.cross_align {
+                CrossAlign::Start | CrossAlign::Stretch => 0.0,
+                CrossAlign::End => cross - size.1,
+                CrossAlign::Center => (cross - size.1) / 2.0,
+                CrossAlign::Baseline if baseline => above - element_baseline,
+                CrossAlign::Baseline => 0.0,
+            };
+            let frame = self.axis.frame(position + (slot - size.0) / 2.0, offset, size.0, size.1);
+            let clip = self.axis.frame(position, 0.0, *slot, cross);
+            self.frames.push(frame);
+            // Content of the shrunk elements may overflow even if their frames fit.
+            let crop = self.overlap == OverlapPolicy::Crop && (overflow || !clip.contains(&frame));
+            self.clips.push(Some(clip).filter(|_| crop));
+            position += slot + between;
+        }
+
+        let (width, height) = self.axis.axes(main, cross);
+        let size = constraints.constrain(width, height);
+        if context.is_right_to_left() {
+            mirror(self.frames.iter_mut().chain(self.clips.iter_mut().flatten()), size.widthf());
+        }
+        self.size = size;
+        size
+    }
+
+    fn paint<C: Children + ?Sized>(&self, elements: &C, canvas: &mut dyn Canvas, rect: Rect) {
+        for (i, (frame, clip)) in self.frames.iter().zip(&self.clips).enumerate() {
+            match clip {
+                Some(clip) => {
+                    canvas.push_clip(clip.offset(rect.x, rect.y));
+                    elements.paint_at(i, canvas, frame.offset(rect.x, rect.y));
+                    canvas.pop_clip();
+                }
+                None => elements.paint_at(i, canvas, frame.offset(rect.x, rect.y)),
+            }
+        }
+    }
+
+    fn ink_overflow<C: Children + ?Sized>(&self, elements: &C) -> Insets {
+        let bounds = Rect::sized(0.0, 0.0, self.size);
+        let ink = self.frames.iter().zip(&self.clips).enumerate().fold(bounds, |ink, (i, (frame, clip))| {
+            let element = *frame + elements.ink_overflow_at(i);
+            let visible = match clip {
+                Some(clip) => element.intersection(clip).unwrap_or_default(),
+                None => element,
+            };
+            ink.union(&visible)
+        });
+        bounds.overflow(&ink)
+    }
+
+    fn hit_test<C: Children + ?Sized>(&self, elements: &C, rect: Rect, point: Point) -> bool {
+        self.frames.iter().zip(&self.clips).enumerate().any(|(i, (frame, clip))| {
+            clip.is_none_or(|clip| clip.offset(rect.x, rect.y).contains_point(point))
+                && elements.hit_test_at(i, frame.offset(rect.x, rect.y), point)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Axis {
+    /// Swap the values for vertical axis so that the first value is along
+    /// the main axis.
+    fn axes<T>(self, horizontal: T, vertical: T) -> (T, T) {
+        match self {
+            Axis::Horizontal => (horizontal, vertical),
+            Axis::Vertical => (vertical, horizontal),
+        }
+    }
+
+    fn constraints(self, min_main: f32, max_main: f32, min_cross: f32, max_cross: f32) -> BoxConstraints {
+        let (min_width, min_height) = self.axes(min_main, min_cross);
+        let (max_width, max_height) = self.axes(max_main, max_cross);
+        BoxConstraints::new(min_width, max_width, min_height, max_height).expect("valid child constraints")
+    }
+
+    fn frame(self, main: f32, cross: f32, main_size: f32, cross_size: f32) -> Rect {
+        let (x, y) = self.axes(main, cross);
+        let (width, height) = self.axes(main_size, cross_size);
+        Rect::new(x, y, width, height)
+    }
+}
+
+/// Elements placed one after another along the main axis.
+pub struct Array<C: Children> {
+    elements: C,
+    line: Line,
+}
+
+impl<C: Children> Array<C> {
+    /// Elements wrapped in [`Flexible`] share the free space along the axis.
+    pub fn new(elements: C, axis: Axis) -> Self {
+        Self { elements, line: Line::new(axis) }
+    }
+
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.line.justify = justify;
+        self
+    }
+
+    pub fn cross_align(mut self, cross_align: CrossAlign) -> Self {
+        self.line.cross_align = cross_align;
+        self
+    }
+
+    /// How to draw the elements when they do not fit into the array.
+    pub fn overlap(mut self, overlap: OverlapPolicy) -> Self {
+        self.line.overlap = overlap;
+        self
+    }
+}
+
+impl<C: Children> Element for Array<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.line.layout(&mut self.elements, constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        first_baseline(&self.elements, &self.line.frames)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.line.hit_test(&self.elements, rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.line.ink_overflow(&self.elements)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.line.paint(&self.elements, canvas, rect);
+    }
+}
+
+impl<C: Children> Composite<C> for Array<C> {
+    fn children(&self) -> &C {
+        &self.elements
+    }
+
+    fn children_mut(&mut self) -> &mut C {
+        self.line.measurements.invalidate();
+        &mut self.elements
+    }
+
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.line.measurements.invalidate_at(index);
+        &mut self.elements
+    }
+}
+
+/// Elements placed one after another from left to right, same as [`Array`] along
+/// [`Axis::Horizontal`].
+pub struct Row<C: Children> {
+    array: Array<C>,
+}
+
+impl<C: Children> Row<C> {
+    pub fn new(elements: C) -> Self {
+        Self { array: Array::new(elements, Axis::Horizontal) }
+    }
+
+    pub fn justify(self, justify: Justify) -> Self {
+        Self { array: self.array.justify(justify) }
+    }
+
+    pub fn cross_align(self, cross_align: CrossAlign) -> Self {
+        Self { array: self.array.cross_align(cross_align) }
+    }
+
+    pub fn overlap(self, overlap: OverlapPolicy) -> Self {
+        Self { array: self.array.overlap(overlap) }
+    }
+}
+
+impl<C: Children> Element for Row<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.array.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.array.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.array.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.array.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.array.paint(canvas, rect);
+    }
+}
+
+impl<C: Children> Composite<C> for Row<C> {
+    fn children(&self) -> &C {
+        self.array.children()
+    }
+
+    fn children_mut(&mut self) -> &mut C {
+        self.array.children_mut()
+    }
+
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.array.children_mut_at(index)
+    }
+}
+
+impl<C: Children> From<Row<C>> for Array<C> {
+    fn from(value: Row<C>) -> Self {
+        value.array
+    }
+}
+
+/// Elements placed one after another from top to bottom, same as [`Array`] along
+/// [`Axis::Vertical`].
+pub struct Column<C: Children> {
+    array: Array<C>,
+}
+
+impl<C: Children> Column<C> {
+    pub fn new(elements: C) -> Self {
+        Self { array: Array::new(elements, Axis::Vertical) }
+    }
+
+    pub fn justify(self, justify: Justify) -> Self {
+        Self { array: self.array.justify(justify) }
+    }
+
+    pub fn cross_align(self, cross_align: CrossAlign) -> Self {
+        Self { array: self.array.cross_align(cross_align) }
+    }
+
+    pub fn overlap(self, overlap: OverlapPolicy) -> Self {
+        Self { array: self.array.overlap(overlap) }
+    }
+}
+
+impl<C: Children> Element for Column<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.array.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.array.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.array.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.array.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.array.paint(canvas, rect);
+    }
+}
+
+impl<C: Children> Composite<C> for Column<C> {
+    fn children(&self) -> &C {
+        self.array.children()
+    }
+
+    fn children_mut(&mut self) -> &mut C {
+        self.array.children_mut()
+    }
+
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.array.children_mut_at(index)
+    }
+}
+
+impl<C: Children> From<Column<C>> for Array<C> {
+    fn from(value: Column<C>) -> Self {
+        value.array
+    }
+}
+
+/// Elements placed one after another along the main axis, continuing on a new
+/// run when there is no more space left on the current one.
+pub struct Wrap<C: Children> {
+    elements: C,
+    axis: Axis,
+    spacing: Unsigned,
+    run_spacing: Unsigned,
+    justify: Justify,
+    run_justify: Justify,
+    cross_align: CrossAlign,
+    frames: Vec<Rect>,
+    size: Size,
+    measurements: Measurements,
+}
+
+impl<C: Children> Wrap<C> {
+    pub fn new(elements: C, axis: Axis) -> Self {
+        Self {
+            elements,
+            axis,
+            spacing: Unsigned::zero(),
+            run_spacing: Unsigned::zero(),
+            justify: Justify::default(),
+            run_justify: Justify::default(),
+            cross_align: CrossAlign::default(),
+            frames: Vec::new(),
+            size: Size::zero(),
+            measurements: Measurements::default(),
+        }
+    }
+
+    /// Space between adjacent elements of a run.
+    pub fn spacing(mut self, spacing: Unsigned) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Space between adjacent runs.
+    pub fn run_spacing(mut self, run_spacing: Unsigned) -> Self {
+        self.run_spacing = run_spacing;
+        self
+    }
+
+    /// Distribution of the free space of each run between its elements.
+    pub fn justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    /// Distribution of the free space across the main axis between the runs.
+    pub fn run_justify(mut self, run_justify: Justify) -> Self {
+        self.run_justify = run_justify;
+        self
+    }
+
+    /// Placement of the elements across the main axis within their run.
+    pub fn cross_align(mut self, cross_align: CrossAlign) -> Self {
+        self.cross_align = cross_align;
+        self
+    }
+}
+
+impl<C: Children> Element for Wrap<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let axis = self.axis;
+        let (min_main, min_cross) = axis.axes(constraints.min_width(), constraints.min_height());
+        let (max_main, max_cross) = axis.axes(constraints.max_width(), constraints.max_height());
+        let spacing: f32 = self.spacing.into();
+        let run_spacing: f32 = self.run_spacing.into();
+
+        self.measurements.begin(self.elements.len(), context);
+        let mut sizes = Vec::with_capacity(self.elements.len());
+        for i in 0..self.elements.len() {
+            let size = self.measurements.layout(&mut self.elements, i, axis.constraints(0.0, max_main, 0.0, max_cross), context);
+            sizes.push(axis.axes(size.widthf(), size.heightf()));
+        }
+
+        // Runs as ranges of the elements along with their main extent.
+        let mut runs: Vec<(std::ops::Range<usize>, f32)> = Vec::new();
+        for (i, size) in sizes.iter().enumerate() {
+            match runs.last_mut() {
+                Some((range, extent)) if *extent + spacing + size.0 <= max_main => {
+                    range.end = i + 1;
+                    *extent += spacing + size.0;
+                }
+                _ => runs.push((i..i + 1, size.0)),
+            }
+        }
+
+        self.measurements.settle(&mut self.elements, context);
+        let baseline = self.cross_align == CrossAlign::Baseline && axis == Axis::Horizontal;
+        let mut run_crosses = Vec::with_capacity(runs.len());
+        for (range, _) in &runs {
+            let cross = if baseline {
+                let (above, below) = range.clone().fold((0.0f32, 0.0f32), |(above, below), i| {
+                    let b = self.elements.baseline_at(i).unwrap_or(sizes[i].1);
+                    (above.max(b), below.max(sizes[i].1 - b))
+                });
+                above + below
+            } else {
+                range.clone().fold(0.0f32, |a, i| a.max(sizes[i].1))
+            };
+            run_crosses.push(cross);
+        }
+
+        let longest = runs.iter().fold(0.0f32, |a, run| a.max(run.1));
+        let total_cross = run_crosses.iter().sum::<f32>() + run_spacing * runs.len().saturating_sub(1) as f32;
+        let main = if max_main.is_finite() && self.justify != Justify::Start { max_main } else { longest };
+        let cross = if max_cross.is_finite() && self.run_justify != Justify::Start { max_cross } else { total_cross };
+        let main = main.clamp(min_main, max_main.max(min_main));
+        let cross = cross.clamp(min_cross, max_cross.max(min_cross));
+
+        self.frames.clear();
+        let (run_leading, run_between) = self.run_justify.distribute(cross - total_cross, runs.len());
+        let mut run_position = run_leading;
+        for ((range, extent), run_cross) in runs.iter().zip(&run_crosses) {
+            let above = range
+                .clone()
+                .fold(0.0f32, |a, i| a.max(self.elements.baseline_at(i).unwrap_or(sizes[i].1)));
+
+            let (leading, between) = self.justify.distribute(main - extent, range.len());
+            let mut position = leading;
+            for i in range.clone() {
+                if self.cross_align == CrossAlign::Stretch && sizes[i].1 < *run_cross {
+                    let size = self.measurements.layout(&mut self.elements, i, axis.constraints(sizes[i].0, sizes[i].0, *run_cross, *run_cross), context);
+                    sizes[i] = axis.axes(size.widthf(), size.heightf());
+                }
+
+                let (main_size, cross_size) = sizes[i];
+                let offset = match self.cross_align {
+                    CrossAlign::Start | CrossAlign::Stretch => 0.0,
+                    CrossAlign::End => run_cross - cross_size,
+                    CrossAlign::Center => (run_cross - cross_size) / 2.0,
+                    CrossAlign::Baseline if baseline => above - self.elements.baseline_at(i).unwrap_or(cross_size),
+                    CrossAlign::Baseline => 0.0,
+                };
+                self.frames.push(axis.frame(position, run_position + offset, main_size, cross_size));
+                position += main_size + spacing + between;
+            }
+            run_position += run_cross + run_spacing + run_between;
+        }
+
+        self.measurements.settle(&mut self.elements, context);
+
+        let (width, height) = axis.axes(main, cross);
+        let size = constraints.constrain(width, height);
+        if context.is_right_to_left() {
+            mirror(&mut self.frames, size.widthf());
+        }
+        self.size = size;
+        size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        first_baseline(&self.elements, &self.frames)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        hit_any(&self.elements, &self.frames, rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        children_overflow(&self.elements, &self.frames, self.size)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        for (i, frame) in self.frames.iter().enumerate() {
+            self.elements.paint_at(i, canvas, frame.offset(rect.x, rect.y));
+        }
+    }
+}
+
+impl<C: Children> Composite<C> for Wrap<C> {
+    fn children(&self) -> &C {
+        &self.elements
+    }
+
+    fn children_mut(&mut self) -> &mut C {
+        self.measurements.invalidate();
+        &mut self.elements
+    }
+
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.measurements.invalidate_at(index);
+        &mut self.elements
+    }
+}
+
+/// Offsets of a stack element from the edges of the stack. The element is
+/// stretched between the opposite edges when both offsets are given.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Positioned {
+    pub top: Option<Unsigned>,
+    pub right: Option<Unsigned>,
+    pub bottom: Option<Unsigned>,
+    pub left: Option<Unsigned>,
+}
+
+/// Size of a stack.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackFit {
+    /// Size of the largest element which is not positioned.
+    #[default]
+    Largest,
+
+    /// All the space available.
+    Expand,
+}
+
+/// Elements drawn one over another. Elements with the higher z-index are drawn
+/// above, ones with the same z-index are drawn in order.
+pub struct Stack<C: Children> {
+    elements: C,
+    fit: StackFit,
+    x: PercentSigned,
+    y: PercentSigned,
+    positions: Vec<Option<Positioned>>,
+    z_indices: Vec<i32>,
+    frames: Vec<Rect>,
+    size: Size,
+    measurements: Measurements,
+}
+
+impl<C: Children> Stack<C> {
+    pub fn new(elements: C) -> Self {
+        let len = elements.len();
+        Self {
+            elements,
+            fit: StackFit::default(),
+            x: PercentSigned::unit_neg(),
+            y: PercentSigned::unit_neg(),
+            positions: vec![None; len],
+            z_indices: vec![0; len],
+            frames: Vec::new(),
+            size: Size::zero(),
+            measurements: Measurements::default(),
+        }
+    }
+
+    pub fn fit(mut self, fit: StackFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Placement of the elements which are not positioned, the same as of [`Align`].
+    /// Top left corner by default.
+    pub fn align(mut self, x: PercentSigned, y: PercentSigned) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Anchor the element at the index to the edges of the stack.
+    ///
+    /// # Panics
+    ///
+    /// If there is no element at the index.
+    pub fn position(mut self, index: usize, position: Positioned) -> Self {
+        self.positions[index] = Some(position);
+        self
+    }
+
+    /// # Panics
+    ///
+    /// If there is no element at the index.
+    pub fn z_index(mut self, index: usize, z_index: i32) -> Self {
+        self.z_indices[index] = z_index;
+        self
+    }
+}
+
+impl<C: Children> Element for Stack<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        // Elements may have been added or removed through `children_mut`.
+        self.positions.resize(self.elements.len(), None);
+        self.z_indices.resize(self.elements.len(), 0);
+        self.measurements.begin(self.elements.len(), context);
+
+        let mut sizes = vec![Size::zero(); self.elements.len()];
+        let (mut width, mut height) = (0.0f32, 0.0f32);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if self.positions[i].is_none() {
+                *size = self.measurements.layout(&mut self.elements, i, constraints.loosen(), context);
+                width = width.max(size.widthf());
+                height = height.max(size.heightf());
+            }
+        }
+
+        if self.fit == StackFit::Expand {
+            width = if constraints.has_bounded_width() { constraints.max_width() } else { width };
+            height = if constraints.has_bounded_height() { constraints.max_height() } else { height };
+        }
+        let stack = constraints.constrain(width, height);
+        let (width, height) = (stack.widthf(), stack.heightf());
+
+        self.frames.clear();
+        let x: f32 = self.x.into();
+        let y: f32 = self.y.into();
+        for (i, size) in sizes.iter().enumerate() {
+            let Some(position) = self.positions[i] else {
+                let left = (width - size.widthf()) * (x + 1.0) / 2.0;
+                let top = (height - size.heightf()) * (y + 1.0) / 2.0;
+                self.frames.push(Rect::sized(left, top, *size));
+                continue;
+            };
+
+            let (min_width, max_width) = positioned_extent(width, position.left, position.right);
+            let (min_height, max_height) = positioned_extent(height, position.top, position.bottom);
+            let limits = BoxConstraints::new(min_width, max_width, min_height, max_height).expect("valid positioned constraints");
+            let size = self.measurements.layout(&mut self.elements, i, limits, context);
+
+            let left = match (position.left, position.right) {
+                (Some(left), _) => left.into(),
+                (None, Some(right)) => width - f32::from(right) - size.widthf(),
+                (None, None) => (width - size.widthf()) * (x + 1.0) / 2.0,
+            };
+            let top = match (position.top, position.bottom) {
+                (Some(top), _) => top.into(),
+                (None, Some(bottom)) => height - f32::from(bottom) - size.heightf(),
+                (None, None) => (height - size.heightf()) * (y + 1.0) / 2.0,
+            };
+            self.frames.push(Rect::sized(left, top, size));
+        }
+
+        self.measurements.settle(&mut self.elements, context);
+        self.size = stack;
+        stack
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        first_baseline(&self.elements, &self.frames)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        hit_any(&self.elements, &self.frames, rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        children_overflow(&self.elements, &self.frames, self.size)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let mut order: Vec<usize> = (0..self.frames.len()).collect();
+        order.sort_by_key(|i| self.z_indices[*i]);
+        for i in order {
+            self.elements.paint_at(i, canvas, self.frames[i].offset(rect.x, rect.y));
+        }
+    }
+}
+
+impl<C: Children> Composite<C> for Stack<C> {
+    fn children(&self) -> &C {
+        &self.elements
+    }
+
+    fn children_mut(&mut self) -> &mut C {
+        self.measurements.invalidate();
+        &mut self.elements
+    }
+
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.measurements.invalidate_at(index);
+        &mut self.elements
+    }
+}
+
+/// Limits of the extent of a positioned element with the offsets from the
+/// edges of the stack of the given extent.
+fn positioned_extent(extent: f32, start: Option<Unsigned>, end: Option<Unsigned>) -> (f32, f32) {
+    match (start, end) {
+        (Some(start), Some(end)) => {
+            let stretched = (extent - f32::from(start) - f32::from(end)).max(0.0);
+            (stretched, stretched)
+        }
+        (Some(offset), None) | (None, Some(offset)) => (0.0, (extent - f32::from(offset)).max(0.0)),
+        (None, None) => (0.0, extent),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Flex(f32);
+
+impl Default for Flex {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Error)]
+pub enum FlexError {
+    #[error("flex cannot be NaN")]
+    NotANumber,
+
+    #[error("flex cannot be negative")]
+    Negative,
+
+    #[error("flex cannot be zero")]
+    Zero,
+
+    #[error("flex cannot be infinite")]
+    Infinite,
+}
+
+impl Flex {
+    pub fn new(flex: f32) -> Result<Self, FlexError> {
+        if flex.is_nan() {
+            Err(FlexError::NotANumber)
+        } else if flex.is_sign_negative() {
+            Err(FlexError::Negative)
+        } else if flex == 0.0 {
+            Err(FlexError::Zero)
+        } else if flex.is_infinite() {
+            Err(FlexError::Infinite)
+        } else {
+            Ok(Self(flex))
+        }
+    }
+}
+
+impl From<Flex> for f32 {
+    fn from(value: Flex) -> Self {
+        value.0
+    }
+}
+
+/// Element taking a share of the free space along the main axis of the array
+/// it is a direct child of, proportional to the flex among the other flexible
+/// elements. The element is laid out with that extent, leaving no room for
+/// its own size along the axis. Outside of arrays it has no effect.
+pub struct Flexible<E: Element> {
+    element: E,
+    flex: Flex,
+}
+
+impl<E: Element> Flexible<E> {
+    pub const fn new(element: E, flex: Flex) -> Self {
+        Self { element, flex }
+    }
+
+    pub fn set_flex(&mut self, flex: Flex) {
+        self.flex = flex;
+    }
+}
+
+impl<E: Element> Element for Flexible<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn flex(&self) -> Option<Flex> {
+        Some(self.flex)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
+    }
+}
+
+impl<E: Element> Layer<E> for Flexible<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// The elements are cropped so that to avoid visual overlapping.
+    /// Note as the default behavior is to center the elements so when cropped only
+    /// center of each element will be visible but if alignment is applied then crop
+    /// is made so that alignment part was visible.
+    /// For left alignment the left part of an element will be visible.
+    Crop,
+
+    /// Stack one over another. Default behavior.
+    #[default]
+    Stack,
+}
+
+pub struct Align<E: Element> {
+    element: E,
+    x: PercentSigned,
+    y: PercentSigned,
+    directional: bool,
+    overlap: OverlapPolicy,
+    frame: Rect,
+    size: Size,
+}
+
+impl<E: Element> Align<E> {
+    pub const fn new(element: E, x: PercentSigned, y: PercentSigned) -> Self {
+        Self {
+            element,
+            x,
+            y,
+            directional: false,
+            overlap: OverlapPolicy::Stack,
+            frame: Rect::new(0.0, 0.0, 0.0, 0.0),
+            size: Size::zero(),
+        }
+    }
+
+    /// Alignment where negative `x` is towards the start of the text direction
+    /// rather than to the left.
+    pub const fn directional(element: E, x: PercentSigned, y: PercentSigned) -> Self {
+        let mut align = Self::new(element, x, y);
+        align.directional = true;
+        align
+    }
+
+    /// How to draw the element when it does not fit. Cropping keeps visible
+    /// the part of the element at the alignment side.
+    pub const fn overlap(mut self, overlap: OverlapPolicy) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    pub const fn x(&self) -> PercentSigned {
+        self.x
+    }
+
+    pub const fn y(&self) -> PercentSigned {
+        self.y
+    }
+
+    pub const fn is_directional(&self) -> bool {
+        self.directional
+    }
+
+    pub const fn left(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_neg(), PercentSigned::zero())
+    }
+
+    pub const fn right(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_pos(), PercentSigned::zero())
+    }
+
+    pub const fn top(element: E) -> Self {
+        Self::new(element, PercentSigned::zero(), PercentSigned::unit_neg())
+    }
+
+    pub const fn bottom(element: E) -> Self {
+        Self::new(element, PercentSigned::zero(), PercentSigned::unit_pos())
+    }
+
+    pub const fn center(element: E) -> Self {
+        Self::new(element, PercentSigned::zero(), PercentSigned::zero())
+    }
+
+    pub const fn top_left(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_neg(), PercentSigned::unit_neg())
+    }
+
+    pub const fn top_right(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_pos(), PercentSigned::unit_neg())
+    }
+
+    pub const fn bottom_left(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_neg(), PercentSigned::unit_pos())
+    }
+
+    pub const fn bottom_right(element: E) -> Self {
+        Self::new(element, PercentSigned::unit_pos(), PercentSigned::unit_pos())
+    }
+
+    pub const fn start(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_neg(), PercentSigned::zero())
+    }
+
+    pub const fn end(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_pos(), PercentSigned::zero())
+    }
+
+    pub const fn top_start(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_neg(), PercentSigned::unit_neg())
+    }
+
+    pub const fn top_end(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_pos(), PercentSigned::unit_neg())
+    }
+
+    pub const fn bottom_start(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_neg(), PercentSigned::unit_pos())
+    }
+
+    pub const fn bottom_end(element: E) -> Self {
+        Self::directional(element, PercentSigned::unit_pos(), PercentSigned::unit_pos())
+    }
+}
+
+impl<E: Element> Element for Align<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let child = self.element.layout(constraints.loosen(), context);
+
+        // Take all the space available to have room for aligning.
+        let width = if constraints.has_bounded_width() { constraints.max_width() } else { child.widthf() };
+        let height = if constraints.has_bounded_height() { constraints.max_height() } else { child.heightf() };
+        let size = constraints.constrain(width, height);
+
+        // Map alignment from [-1; 1] to the fraction of free space before the element.
+        let mut x: f32 = self.x.into();
+        let y: f32 = self.y.into();
+        if self.directional && context.is_right_to_left() {
+            x = -x;
+        }
+        let left = (size.widthf() - child.widthf()) * (x + 1.0) / 2.0;
+        let top = (size.heightf() - child.heightf()) * (y + 1.0) / 2.0;
+        self.frame = Rect::sized(left, top, child);
+        self.size = size;
+        size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline().map(|baseline| self.frame.y + baseline)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        let frame = self.frame.offset(rect.x, rect.y);
+        let cropped = self.overlap == OverlapPolicy::Crop && !rect.contains(&frame);
+        (!cropped || rect.contains_point(point)) && self.element.hit_test(frame, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        let bounds = Rect::sized(0.0, 0.0, self.size);
+        if self.overlap == OverlapPolicy::Crop && !bounds.contains(&self.frame) {
+            return Insets::zero();
+        }
+        bounds.overflow(&(self.frame + self.element.ink_overflow()))
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let frame = self.frame.offset(rect.x, rect.y);
+        if self.overlap == OverlapPolicy::Crop && !rect.contains(&frame) {
+            canvas.push_clip(rect);
+            self.element.paint(canvas, frame);
+            canvas.pop_clip();
+        } else {
+            self.element.paint(canvas, frame);
+        }
+    }
+}
+
+impl<E: Element> Layer<E> for Align<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Empty space around the element.
+///
+/// Directional spaces treat left and right insets as the ones at the start and
+/// at the end of the text direction and swap them for right-to-left text.
+pub struct Space<E: Element> {
+    top: Length,
+    right: Length,
+    bottom: Length,
+    left: Length,
+    insets: Insets,
+    directional: bool,
+    mirrored: bool,
+    element: E,
+}
+
+impl<E: Element> Space<E> {
+    pub fn new(element: E, insets: Insets) -> Self {
+        Self::trbl(element, insets.top, insets.right, insets.bottom, insets.left)
+    }
+
+    pub fn trbl(
+        element: E,
+        top: impl Into<Length>,
+        right: impl Into<Length>,
+        bottom: impl Into<Length>,
+        left: impl Into<Length>,
+    ) -> Self {
+        Self {
+            top: top.into(),
+            right: right.into(),
+            bottom: bottom.into(),
+            left: left.into(),
+            insets: Insets::zero(),
+            directional: false,
+            mirrored: false,
+            element,
+        }
+    }
+
+    /// Directional space with the insets given in the order of top, end, bottom, start.
+    pub fn tebs(
+        element: E,
+        top: impl Into<Length>,
+        end: impl Into<Length>,
+        bottom: impl Into<Length>,
+        start: impl Into<Length>,
+    ) -> Self {
+        let mut space = Self::trbl(element, top, end, bottom, start);
+        space.directional = true;
+        space
+    }
+
+    pub const fn is_directional(&self) -> bool {
+        self.directional
+    }
+
+    /// Insets in logical pixels after the last layout, with percents of the
+    /// horizontal ones taken of the width and of the vertical ones of the height
+    /// given by the parent.
+    pub const fn insets(&self) -> Insets {
+        self.insets
+    }
+
+    pub const fn top(&self) -> Length {
+        self.top
+    }
+
+    pub fn topf(&self) -> f32 {
+        self.insets.top.into()
+    }
+
+    pub const fn bottom(&self) -> Length {
+        self.bottom
+    }
+
+    pub fn bottomf(&self) -> f32 {
+        self.insets.bottom.into()
+    }
+
+    pub const fn left(&self) -> Length {
+        self.left
+    }
+
+    pub fn leftf(&self) -> f32 {
+        self.insets.left.into()
+    }
+
+    pub const fn right(&self) -> Length {
+        self.right
+    }
+
+    pub fn rightf(&self) -> f32 {
+        self.insets.right.into()
+    }
+
+    pub fn all(element: E, space: impl Into<Length>) -> Self {
+        let space = space.into();
+        Self::trbl(element, space, space, space, space)
+    }
+
+    pub fn horizontal(element: E, left: impl Into<Length>, right: impl Into<Length>) -> Self {
+        Self::trbl(element, Length::zero(), right, Length::zero(), left)
+    }
+
+    pub fn vertical(element: E, top: impl Into<Length>, bottom: impl Into<Length>) -> Self {
+        Self::trbl(element, top, Length::zero(), bottom, Length::zero())
+    }
+
+    pub fn start_end(element: E, start: impl Into<Length>, end: impl Into<Length>) -> Self {
+        Self::tebs(element, Length::zero(), end, Length::zero(), start)
+    }
+}
+
+impl<E: Element> Element for Space<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let width = constraints.max_width();
+        let height = constraints.max_height();
+        self.insets = Insets::trbl(
+            self.top.resolve(context, height),
+            self.right.resolve(context, width),
+            self.bottom.resolve(context, height),
+            self.left.resolve(context, width),
+        );
+
+        let horizontal = self.insets.horizontal();
+        let vertical = self.insets.vertical();
+        let child = self.element.layout(constraints.deflate(horizontal, vertical), context);
+        self.mirrored = self.directional && context.is_right_to_left();
+        constraints.constrain_min(child.widthf() + horizontal, child.heightf() + vertical)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline().map(|baseline| self.topf() + baseline)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        let insets = if self.mirrored { self.insets.mirrored() } else { self.insets };
+        self.element.hit_test(rect - insets, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        let insets = if self.mirrored { self.insets.mirrored() } else { self.insets };
+        self.element.ink_overflow().saturating_sub(insets)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let insets = if self.mirrored { self.insets.mirrored() } else { self.insets };
+        self.element.paint(canvas, rect - insets);
+    }
+}
+
+impl<E: Element> Layer<E> for Space<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Text direction of the element and all its descendants.
+pub struct Directionality<E: Element> {
+    element: E,
+    direction: Direction,
+}
+
+impl<E: Element> Directionality<E> {
+    pub const fn new(element: E, direction: Direction) -> Self {
+        Self { element, direction }
+    }
+
+    pub const fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+impl<E: Element> Element for Directionality<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, &context.with_direction(self.direction))
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
 }
 
-impl<E: Element> Element for Row<E> {
-    fn width(&self) -> f32 {
-        self.elements
-            .iter()
-            .map(|e| e.width())
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0)
+impl<E: Element> Layer<E> for Directionality<E> {
+    fn inner(&self) -> &E {
+        &self.element
     }
 
-    fn height(&self) -> f32 {
-        self.elements
-            .iter()
-            .map(|e| e.height())
-            .sum()
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
     }
 }
 
-impl<E: Element> Composite<E> for Row<E> {}
+/// Style of the [inherited](crate::text::Text::inherited) text and the size
+/// [`Length::Em`] is relative to in the element and all its descendants.
+pub struct TextStyle<E: Element> {
+    element: E,
+    style: Style,
+}
+
+impl<E: Element> TextStyle<E> {
+    pub const fn new(element: E, style: Style) -> Self {
+        Self { element, style }
+    }
 
-pub struct Column<E: Element> {
-    elements: Vec<E>,
+    pub const fn style(&self) -> &Style {
+        &self.style
+    }
 }
 
-impl<E: Element> Column<E> {
-    pub fn new(elements: Vec<E>) -> Self {
-        Self { elements }
+impl<E: Element> Element for TextStyle<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, &context.with_text_style(self.style.clone()))
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
 }
 
-impl<E: Element> Element for Column<E> {
-    fn width(&self) -> f32 {
-        self.elements
-            .iter()
-            .map(|e| e.width())
-            .sum()
+impl<E: Element> Layer<E> for TextStyle<E> {
+    fn inner(&self) -> &E {
+        &self.element
     }
 
-    fn height(&self) -> f32 {
-        self.elements
-            .iter()
-            .map(|e| e.height())
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0)
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
     }
 }
 
-impl<E: Element> Composite<E> for Column<E> {}
+/// Size of a row or a column of a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    Fixed(Unsigned),
 
-pub enum Direction {
-    Horizontal,
-    Vertical,
+    /// Share of the space left after the other tracks proportional to the flex
+    /// among other flexible tracks. Sized as [`Track::Auto`] when the grid is
+    /// not limited along the axis.
+    Flex(Flex),
+
+    /// Size of the largest element in the track.
+    Auto,
 }
 
-pub struct Array<E: Element> {
-    elements: Vec<E>,
-    direction: Direction,
+/// Position of an element in a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    row: usize,
+    column: usize,
+    rows: usize,
+    columns: usize,
+    align: Option<(PercentSigned, PercentSigned)>,
+}
+
+impl Cell {
+    /// Cell at the row and column counted from zero, spanning one track along
+    /// each axis. The element is stretched to fill the cell.
+    pub const fn new(row: usize, column: usize) -> Self {
+        Self { row, column, rows: 1, columns: 1, align: None }
+    }
+
+    /// Make the cell span the given number of rows and columns. Spans of zero
+    /// are treated as one.
+    pub const fn span(mut self, rows: usize, columns: usize) -> Self {
+        self.rows = if rows == 0 { 1 } else { rows };
+        self.columns = if columns == 0 { 1 } else { columns };
+        self
+    }
+
+    /// Keep the natural size of the element and place it within the cell the
+    /// same way as [`Align`] does.
+    pub const fn align(mut self, x: PercentSigned, y: PercentSigned) -> Self {
+        self.align = Some((x, y));
+        self
+    }
+
+    pub const fn row(&self) -> usize {
+        self.row
+    }
+
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// Elements placed in the cells formed by rows and columns.
+///
+/// Elements fill the cells row by row in order unless placed explicitly with
+/// [`Grid::cell`]. Cells outside of the given tracks add [`Track::Auto`] tracks.
+pub struct Grid<C: Children> {
+    elements: C,
+    rows: Vec<Track>,
+    columns: Vec<Track>,
+    cells: Vec<Cell>,
+    row_gap: Unsigned,
+    column_gap: Unsigned,
+    frames: Vec<Rect>,
+    size: Size,
+    measurements: Measurements,
 }
 
-impl<E: Element> Array<E> {
-    pub fn new(elements: Vec<E>, direction: Direction) -> Self {
-        Self { elements, direction }
+impl<C: Children> Grid<C> {
+    pub fn new(elements: C, rows: Vec<Track>, columns: Vec<Track>) -> Self {
+        let mut grid = Self {
+            elements,
+            rows,
+            columns,
+            cells: Vec::new(),
+            row_gap: Unsigned::zero(),
+            column_gap: Unsigned::zero(),
+            frames: Vec::new(),
+            size: Size::zero(),
+            measurements: Measurements::default(),
+        };
+        grid.place();
+        grid
+    }
+
+    /// Put the elements without a cell into the cells following the previous
+    /// elements row by row.
+    fn place(&mut self) {
+        let per_row = self.columns.len().max(1);
+        self.cells.truncate(self.elements.len());
+        for i in self.cells.len()..self.elements.len() {
+            self.cells.push(Cell::new(i / per_row, i % per_row));
+        }
+    }
+
+    /// Place the element at the index into the cell.
+    ///
+    /// # Panics
+    ///
+    /// If there is no element at the index.
+    pub fn cell(mut self, index: usize, cell: Cell) -> Self {
+        self.cells[index] = cell;
+        self
+    }
+
+    /// Space between adjacent rows and adjacent columns.
+    pub fn gap(mut self, row_gap: Unsigned, column_gap: Unsigned) -> Self {
+        self.row_gap = row_gap;
+        self.column_gap = column_gap;
+        self
+    }
+
+    pub fn row_gap(&self) -> Unsigned {
+        self.row_gap
+    }
+
+    pub fn column_gap(&self) -> Unsigned {
+        self.column_gap
     }
 }
 
-impl<E: Element> Element for Array<E> {
-    fn width(&self) -> f32 {
-        match self.direction {
-            Direction::Horizontal => self.elements
-                .iter()
-                .map(|e| e.width())
-                .sum(),
-            Direction::Vertical => self.elements
-                .iter()
-                .map(|e| e.width())
-                .max_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or(0.0),
+impl<C: Children> Element for Grid<C> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        // Elements may have been added or removed through `children_mut`.
+        self.place();
+        self.measurements.begin(self.elements.len(), context);
+
+        let row_count = self.cells.iter().map(|c| c.row + c.rows).fold(self.rows.len(), usize::max);
+        let column_count = self.cells.iter().map(|c| c.column + c.columns).fold(self.columns.len(), usize::max);
+        let row_gap: f32 = self.row_gap.into();
+        let column_gap: f32 = self.column_gap.into();
+
+        // Columns are sized by the natural widths, then the rows by the heights
+        // the elements take within their columns.
+        let mut items = Vec::with_capacity(self.cells.len());
+        for (i, cell) in self.cells.iter().enumerate() {
+            let size = self.measurements.layout(&mut self.elements, i, BoxConstraints::unbounded(), context);
+            items.push((cell.column, cell.columns, size.widthf()));
+        }
+        let columns = tracks(&self.columns, column_count, constraints.max_width(), column_gap, &items);
+        let column_starts = starts(&columns, column_gap);
+
+        items.clear();
+        for (i, cell) in self.cells.iter().enumerate() {
+            let width = span(&columns, cell.column, cell.columns, column_gap);
+            let limits = BoxConstraints::new(0.0, width, 0.0, f32::INFINITY).expect("valid cell constraints");
+            let size = self.measurements.layout(&mut self.elements, i, limits, context);
+            items.push((cell.row, cell.rows, size.heightf()));
         }
+        let rows = tracks(&self.rows, row_count, constraints.max_height(), row_gap, &items);
+        let row_starts = starts(&rows, row_gap);
+
+        self.frames.clear();
+        for (i, cell) in self.cells.iter().enumerate() {
+            let area = Rect::new(
+                column_starts[cell.column],
+                row_starts[cell.row],
+                span(&columns, cell.column, cell.columns, column_gap),
+                span(&rows, cell.row, cell.rows, row_gap),
+            );
+            let area_size = Size::new(Unsigned::clamped(area.width), Unsigned::clamped(area.height));
+            let frame = match cell.align {
+                None => Rect::sized(area.x, area.y, self.measurements.layout(&mut self.elements, i, BoxConstraints::tight(area_size), context)),
+                Some((x, y)) => {
+                    let size = self.measurements.layout(&mut self.elements, i, BoxConstraints::loose(area_size), context);
+                    let x: f32 = x.into();
+                    let y: f32 = y.into();
+                    let left = (area.width - size.widthf()) * (x + 1.0) / 2.0;
+                    let top = (area.height - size.heightf()) * (y + 1.0) / 2.0;
+                    Rect::sized(area.x + left, area.y + top, size)
+                }
+            };
+            self.frames.push(frame);
+        }
+
+        self.measurements.settle(&mut self.elements, context);
+
+        let size = constraints.constrain(span(&columns, 0, column_count, column_gap), span(&rows, 0, row_count, row_gap));
+        if context.is_right_to_left() {
+            mirror(&mut self.frames, size.widthf());
+        }
+        self.size = size;
+        size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        first_baseline(&self.elements, &self.frames)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        hit_any(&self.elements, &self.frames, rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        children_overflow(&self.elements, &self.frames, self.size)
     }
 
-    fn height(&self) -> f32 {
-        match self.direction {
-            Direction::Horizontal => self.elements
-                .iter()
-                .map(|e| e.height())
-                .max_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap_or(0.0),
-            Direction::Vertical => self.elements
-                .iter()
-                .map(|e| e.height())
-                .sum(),
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        for (i, frame) in self.frames.iter().enumerate() {
+            self.elements.paint_at(i, canvas, frame.offset(rect.x, rect.y));
         }
     }
 }
 
-impl<E: Element> Composite<E> for Array<E> {}
+impl<C: Children> Composite<C> for Grid<C> {
+    fn children(&self) -> &C {
+        &self.elements
+    }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct Flex(f32);
+    fn children_mut(&mut self) -> &mut C {
+        self.measurements.invalidate();
+        &mut self.elements
+    }
 
-impl Default for Flex {
-    fn default() -> Self {
-        Self(1.0)
+    fn children_mut_at(&mut self, index: usize) -> &mut C {
+        self.measurements.invalidate_at(index);
+        &mut self.elements
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Error)]
-pub enum FlexError {
-    #[error("flex cannot be NaN")]
-    NotANumber,
+/// Sizes of the tracks along an axis given the `(first track, span, natural size)`
+/// of each element.
+fn tracks(tracks: &[Track], count: usize, available: f32, gap: f32, items: &[(usize, usize, f32)]) -> Vec<f32> {
+    let track = |i: usize| match tracks.get(i) {
+        Some(Track::Flex(_)) if !available.is_finite() => Track::Auto,
+        Some(track) => *track,
+        None => Track::Auto,
+    };
 
-    #[error("flex cannot be negative")]
-    Negative,
+    let mut sizes: Vec<f32> = (0..count)
+        .map(|i| match track(i) {
+            Track::Fixed(size) => size.into(),
+            Track::Flex(_) | Track::Auto => 0.0,
+        })
+        .collect();
 
-    #[error("flex cannot be zero")]
-    Zero,
+    for &(start, _, natural) in items.iter().filter(|item| item.1 == 1) {
+        if track(start) == Track::Auto {
+            sizes[start] = sizes[start].max(natural);
+        }
+    }
 
-    #[error("flex cannot be infinite")]
-    Infinite,
-}
+    // Spanning elements grow the auto tracks they cross unless a flexible track
+    // can take the rest of their size.
+    for &(start, span, natural) in items.iter().filter(|item| item.1 > 1) {
+        let range = start..start + span;
+        if range.clone().any(|i| matches!(track(i), Track::Flex(_))) {
+            continue;
+        }
 
-impl Flex {
-    pub fn new(flex: f32) -> Result<Self, FlexError> {
-        if flex.is_nan() {
-            Err(FlexError::NotANumber)
-        } else if flex.is_sign_negative() {
-            Err(FlexError::Negative)
-        } else if flex == 0.0 {
-            Err(FlexError::Zero)
-        } else if flex.is_infinite() {
-            Err(FlexError::Infinite)
-        } else {
-            Ok(Self(flex))
+        let auto: Vec<usize> = range.clone().filter(|i| track(*i) == Track::Auto).collect();
+        let missing = natural - sizes[range].iter().sum::<f32>() - gap * (span - 1) as f32;
+        if missing > 0.0 && !auto.is_empty() {
+            for i in &auto {
+                sizes[*i] += missing / auto.len() as f32;
+            }
         }
     }
+
+    let total_flex: f32 = (0..count)
+        .filter_map(|i| match track(i) {
+            Track::Flex(flex) => Some(f32::from(flex)),
+            _ => None,
+        })
+        .sum();
+    if total_flex > 0.0 {
+        let used = sizes.iter().sum::<f32>() + gap * count.saturating_sub(1) as f32;
+        let free = (available - used).max(0.0);
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if let Track::Flex(flex) = track(i) {
+                *size = free * f32::from(flex) / total_flex;
+            }
+        }
+    }
+
+    sizes
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OverlapPolicy {
-    /// The elements are cropped so that to avoid visual overlapping.
-    /// Note as the default behavior is to center the elements so when cropped only
-    /// center of each element will be visible but if alignment is applied then crop
-    /// is made so that alignment part was visible.
-    /// For left alignment the left part of an element will be visible.
-    Crop,
+/// Positions of the tracks with given sizes, saturating at the largest finite
+/// value.
+fn starts(sizes: &[f32], gap: f32) -> Vec<f32> {
+    let mut position = 0.0;
+    sizes
+        .iter()
+        .map(|size| {
+            let start = position;
+            position = (position + size + gap).min(f32::MAX);
+            start
+        })
+        .collect()
+}
 
-    /// Stack one over another. Default behavior.
-    #[default]
-    Stack,
+/// Extent of the tracks together with the gaps between them, saturating at the
+/// largest finite value.
+fn span(sizes: &[f32], start: usize, count: usize, gap: f32) -> f32 {
+    let tracks = &sizes[start..start + count];
+    let extent = tracks.iter().sum::<f32>() + gap * count.saturating_sub(1) as f32;
+    Unsigned::clamped(extent).into()
 }
 
-pub struct Align<E: Element> {
+/// Fixed width, height or both of the element as far as the parent allows.
+pub struct SizedBox<E: Element> {
     element: E,
-    x: PercentSigned,
-    y: PercentSigned,
+    width: Option<Length>,
+    height: Option<Length>,
 }
 
-impl<E: Element> Align<E> {
-    pub const fn new(element: E, x: PercentSigned, y: PercentSigned) -> Self {
-        Self { element, x, y }
+impl<E: Element> SizedBox<E> {
+    /// Percents are taken of the maximum size given by the parent.
+    pub const fn new(element: E, width: Option<Length>, height: Option<Length>) -> Self {
+        Self { element, width, height }
     }
 
-    pub const fn x(&self) -> PercentSigned {
-        self.x
+    pub const fn exact(element: E, size: Size) -> Self {
+        Self::new(element, Some(Length::Px(size.width())), Some(Length::Px(size.height())))
     }
 
-    pub const fn y(&self) -> PercentSigned {
-        self.y
+    pub const fn width(&self) -> Option<Length> {
+        self.width
     }
 
-    pub const fn left(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_neg(), PercentSigned::zero())
+    pub const fn height(&self) -> Option<Length> {
+        self.height
     }
+}
 
-    pub const fn right(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_pos(), PercentSigned::zero())
+impl<E: Element> Element for SizedBox<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let width = self.width.map(|width| width.resolvef(context, constraints.max_width()));
+        let height = self.height.map(|height| height.resolvef(context, constraints.max_height()));
+        let constraints = constraints.tighten(width, height);
+        let child = self.element.layout(constraints, context);
+        constraints.constrain(child.widthf(), child.heightf())
     }
 
-    pub const fn top(element: E) -> Self {
-        Self::new(element, PercentSigned::zero(), PercentSigned::unit_neg())
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
     }
 
-    pub const fn bottom(element: E) -> Self {
-        Self::new(element, PercentSigned::zero(), PercentSigned::unit_pos())
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
     }
 
-    pub const fn center(element: E) -> Self {
-        Self::new(element, PercentSigned::zero(), PercentSigned::zero())
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
     }
 
-    pub const fn top_left(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_neg(), PercentSigned::unit_neg())
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
+}
 
-    pub const fn top_right(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_pos(), PercentSigned::unit_neg())
+impl<E: Element> Layer<E> for SizedBox<E> {
+    fn inner(&self) -> &E {
+        &self.element
     }
 
-    pub const fn bottom_left(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_neg(), PercentSigned::unit_pos())
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
     }
+}
 
-    pub const fn bottom_right(element: E) -> Self {
-        Self::new(element, PercentSigned::unit_pos(), PercentSigned::unit_pos())
+/// Additional constraints on the size of the element, applied as far as the
+/// parent allows.
+pub struct ConstrainedBox<E: Element> {
+    element: E,
+    constraints: BoxConstraints,
+}
+
+impl<E: Element> ConstrainedBox<E> {
+    pub const fn new(element: E, constraints: BoxConstraints) -> Self {
+        Self { element, constraints }
+    }
+
+    pub const fn constraints(&self) -> BoxConstraints {
+        self.constraints
     }
 }
 
-impl<E: Element> Element for Align<E> {
-    fn width(&self) -> f32 {
-        self.element.width()
+impl<E: Element> Element for ConstrainedBox<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let constraints = constraints.enforce(self.constraints);
+        let child = self.element.layout(constraints, context);
+        constraints.constrain(child.widthf(), child.heightf())
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
     }
 
-    fn height(&self) -> f32 {
-        self.element.height()
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
 }
 
-impl<E: Element> Layer<E> for Align<E> {
+impl<E: Element> Layer<E> for ConstrainedBox<E> {
     fn inner(&self) -> &E {
         &self.element
     }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
 }
 
-pub struct Space<E: Element> {
-    top: Unsigned,
-    bottom: Unsigned,
-    left: Unsigned,
-    right: Unsigned,
+/// The largest size with the given ratio of width to height the parent allows.
+///
+/// When neither extent is limited, the width of the element is used.
+pub struct AspectRatio<E: Element> {
     element: E,
+    ratio: Positive,
 }
 
-impl<E: Element> Space<E> {
-    pub const fn trbl(element: E, top: Unsigned, right: Unsigned, bottom: Unsigned, left: Unsigned) -> Self {
-        Self { top, bottom, left, right, element }
+impl<E: Element> AspectRatio<E> {
+    pub const fn new(element: E, ratio: Positive) -> Self {
+        Self { element, ratio }
     }
 
-    pub const fn top(&self) -> Unsigned {
-        self.top
+    pub const fn ratio(&self) -> Positive {
+        self.ratio
     }
+}
 
-    pub fn topf(&self) -> f32 {
-        self.top.into()
+impl<E: Element> Element for AspectRatio<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let ratio: f32 = self.ratio.into();
+        let mut width = if constraints.has_bounded_width() {
+            constraints.max_width()
+        } else if constraints.has_bounded_height() {
+            constraints.max_height() * ratio
+        } else {
+            self.element.layout(constraints.loosen(), context).widthf()
+        };
+        let mut height = width / ratio;
+
+        if height > constraints.max_height() {
+            height = constraints.max_height();
+            width = height * ratio;
+        }
+        if width < constraints.min_width() {
+            width = constraints.min_width();
+            height = width / ratio;
+        }
+        if height < constraints.min_height() {
+            height = constraints.min_height();
+            width = height * ratio;
+        }
+
+        let size = constraints.constrain(width, height);
+        self.element.layout(BoxConstraints::tight(size), context);
+        size
     }
 
-    pub const fn bottom(&self) -> Unsigned {
-        self.bottom
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
     }
 
-    pub fn bottomf(&self) -> f32 {
-        self.bottom.into()
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
     }
 
-    pub const fn left(&self) -> Unsigned {
-        self.left
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
     }
 
-    pub fn leftf(&self) -> f32 {
-        self.left.into()
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
+}
 
-    pub const fn right(&self) -> Unsigned {
-        self.right
+impl<E: Element> Layer<E> for AspectRatio<E> {
+    fn inner(&self) -> &E {
+        &self.element
     }
 
-    pub fn rightf(&self) -> f32 {
-        self.right.into()
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Width, height or both of the element as a fraction of the space available.
+/// Fractions along the axes the parent does not limit are ignored.
+pub struct FractionallySized<E: Element> {
+    element: E,
+    width: Option<PercentUnsigned>,
+    height: Option<PercentUnsigned>,
+}
+
+impl<E: Element> FractionallySized<E> {
+    pub const fn new(element: E, width: Option<PercentUnsigned>, height: Option<PercentUnsigned>) -> Self {
+        Self { element, width, height }
     }
 
-    pub const fn all(element: E, space: Unsigned) -> Self {
-        Self::trbl(element, space, space, space, space)
+    pub const fn width(&self) -> Option<PercentUnsigned> {
+        self.width
+    }
+
+    pub const fn height(&self) -> Option<PercentUnsigned> {
+        self.height
+    }
+}
+
+impl<E: Element> Element for FractionallySized<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let width = self
+            .width
+            .filter(|_| constraints.has_bounded_width())
+            .map(|fraction| constraints.max_width() * f32::from(fraction));
+        let height = self
+            .height
+            .filter(|_| constraints.has_bounded_height())
+            .map(|fraction| constraints.max_height() * f32::from(fraction));
+
+        let constraints = constraints.tighten(width, height);
+        let child = self.element.layout(constraints, context);
+        constraints.constrain(child.widthf(), child.heightf())
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
     }
 
-    pub const fn horizontal(element: E, left: Unsigned, right: Unsigned) -> Self {
-        Self::trbl(element, Unsigned::zero(), right, Unsigned::zero(), left)
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
     }
 
-    pub const fn vertical(element: E, top: Unsigned, bottom: Unsigned) -> Self {
-        Self::trbl(element, top, Unsigned::zero(), bottom, Unsigned::zero())
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
     }
 }
 
-impl<E: Element> Element for Space<E> {
-    fn width(&self) -> f32 {
-        self.element.width() + self.leftf() + self.rightf()
+impl<E: Element> Layer<E> for FractionallySized<E> {
+    fn inner(&self) -> &E {
+        &self.element
     }
 
-    fn height(&self) -> f32 {
-        self.element.height() + self.topf() + self.bottomf()
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
     }
 }
 
-impl<E: Element> Layer<E> for Space<E> {
+/// Result of the last layout of an element, valid while neither the element
+/// nor the constraints and the context it is laid out with change.
+#[derive(Default, Debug, Clone)]
+pub struct LayoutCache {
+    last: Option<(BoxConstraints, Context, Size)>,
+}
+
+impl LayoutCache {
+    pub const fn new() -> Self {
+        Self { last: None }
+    }
+
+    /// Size of the last layout if it was made with the same constraints and context.
+    pub fn get(&self, constraints: BoxConstraints, context: &Context) -> Option<Size> {
+        match &self.last {
+            Some((last_constraints, last_context, size)) if *last_constraints == constraints && last_context == context => Some(*size),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&mut self, constraints: BoxConstraints, context: &Context, size: Size) {
+        self.last = Some((constraints, context.clone(), size));
+    }
+
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.last.is_some()
+    }
+}
+
+/// Element laid out again only when the constraints or the context change, or
+/// when it is changed through [`Layer::inner_mut`] or [`Cached::update`].
+///
+/// Since changing a descendant requires mutable access through all of its
+/// ancestors, caching elements along the path invalidate themselves while
+/// the rest of the tree keeps the cached layout. Containers keep the sizes of
+/// the children other than the one changed through
+/// [`Composite::children_mut_at`] the same way, so this is mostly useful for
+/// elements that are costly to lay out, such as text.
+pub struct Cached<E: Element> {
+    element: E,
+    cache: LayoutCache,
+}
+
+impl<E: Element> Cached<E> {
+    pub const fn new(element: E) -> Self {
+        Self { element, cache: LayoutCache::new() }
+    }
+
+    /// Apply the update to the element and mark it for layout.
+    pub fn update<U: Update<Element = E>>(&mut self, update: U)
+    where
+        E: Alive,
+    {
+        update.apply(&mut self.element);
+        self.cache.invalidate();
+    }
+
+    /// Mark the element for layout, for changes the cache cannot track such as
+    /// newly registered fonts.
+    pub fn invalidate(&mut self) {
+        self.cache.invalidate();
+    }
+}
+
+impl<E: Element> Element for Cached<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        if let Some(size) = self.cache.get(constraints, context) {
+            return size;
+        }
+
+        let size = self.element.layout(constraints, context);
+        self.cache.insert(constraints, context, size);
+        size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        self.element.paint(canvas, rect);
+    }
+}
+
+impl<E: Element> Layer<E> for Cached<E> {
     fn inner(&self) -> &E {
         &self.element
     }
+
+    fn inner_mut(&mut self) -> &mut E {
+        self.cache.invalidate();
+        &mut self.element
+    }
+}
+
+impl<E: Alive> Alive for Cached<E> {
+    fn init(&mut self) {
+        self.element.init();
+        self.cache.invalidate();
+    }
+
+    fn refresh(&mut self) {
+        self.element.refresh();
+        self.cache.invalidate();
+    }
 }
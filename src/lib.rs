@@ -1,10 +1,13 @@
 pub mod base;
+pub mod color;
 pub mod math;
 pub mod layout;
 pub mod image;
 pub mod text;
 pub mod path;
 pub mod scroll;
-mod render;
+pub mod transform;
+pub mod blend;
+pub mod render;
 pub mod dynamic;
 pub mod animation;
@@ -1,13 +1,24 @@
 use thiserror::Error;
 
+/// Largest finite value of the same sign in place of an overflowed infinity.
+fn saturate(value: f32) -> f32 {
+    value.clamp(-f32::MAX, f32::MAX)
+}
+
+fn divisor(value: f32) -> f32 {
+    assert!(value != 0.0, "attempt to divide by zero");
+    value
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "f32", into = "f32"))]
 pub struct PercentSigned(f32);
 
 impl Eq for PercentSigned {}
 
 impl PartialOrd for PercentSigned {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() })
+        Some(self.cmp(other))
     }
 }
 
@@ -46,6 +57,9 @@ impl PercentSigned {
         Ok(Self(value))
     }
 
+    /// # Safety
+    ///
+    /// The value must pass the checks of [`Self::new`].
     pub unsafe fn new_unchecked(value: f32) -> Self {
         debug_assert!(Self::new(value).is_ok());
         Self(value)
@@ -62,6 +76,53 @@ impl PercentSigned {
     pub const fn zero() -> Self {
         Self(0.0)
     }
+
+    /// Nearest valid value, zero for NaN.
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            return Self::zero();
+        }
+
+        Self(value.clamp(-1.0, 1.0))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self::clamped(self.0 + rhs.0)
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::clamped(self.0 - rhs.0)
+    }
+}
+
+impl std::ops::Neg for PercentSigned {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl std::ops::Mul for PercentSigned {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl std::ops::MulAssign for PercentSigned {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
 }
 
 impl From<PercentSigned> for f32 {
@@ -99,13 +160,14 @@ impl std::fmt::Display for PercentSigned {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "f32", into = "f32"))]
 pub struct Unsigned(f32);
 
 impl Eq for Unsigned {}
 
 impl PartialOrd for Unsigned {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() })
+        Some(self.cmp(other))
     }
 }
 
@@ -144,6 +206,9 @@ impl Unsigned {
         Ok(Self(value))
     }
 
+    /// # Safety
+    ///
+    /// The value must pass the checks of [`Self::new`].
     pub unsafe fn new_unchecked(value: f32) -> Self {
         debug_assert!(Self::new(value).is_ok());
         Self(value)
@@ -156,6 +221,57 @@ impl Unsigned {
     pub const fn one() -> Self {
         Self(1.0)
     }
+
+    pub const fn largest() -> Self {
+        Self(f32::MAX)
+    }
+
+    /// Nearest valid value, zero for NaN.
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            return Self::zero();
+        }
+
+        Self(value.clamp(0.0, f32::MAX))
+    }
+
+    /// Sum unless it overflows.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    /// Difference unless it is negative.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    /// Product unless it overflows.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 * rhs.0).ok()
+    }
+
+    /// Quotient unless the divisor is zero or the quotient overflows.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 / rhs.0).ok()
+    }
+
+    /// Remainder unless the divisor is zero.
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 % rhs.0).ok()
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self::clamped(self.0 + rhs.0)
+    }
+
+    /// Difference, or zero if it is negative.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::clamped(self.0 - rhs.0)
+    }
+
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        Self::clamped(self.0 * rhs.0)
+    }
 }
 
 impl From<Unsigned> for f32 {
@@ -186,11 +302,12 @@ impl TryFrom<f64> for Unsigned {
     }
 }
 
+/// Saturates at the largest finite value.
 impl std::ops::Add for Unsigned {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 + rhs.0) }
+        Self(saturate(self.0 + rhs.0))
     }
 }
 
@@ -200,25 +317,22 @@ impl std::ops::AddAssign for Unsigned {
     }
 }
 
+/// Difference may be negative, see [`Unsigned::saturating_sub`] and
+/// [`Unsigned::checked_sub`] to stay unsigned.
 impl std::ops::Sub for Unsigned {
-    type Output = Self;
+    type Output = Number;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 - rhs.0) }
-    }
-}
-
-impl std::ops::SubAssign for Unsigned {
-    fn sub_assign(&mut self, rhs: Self) {
-        *self = *self - rhs;
+        Number(self.0 - rhs.0)
     }
 }
 
+/// Saturates at the largest finite value.
 impl std::ops::Mul for Unsigned {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 * rhs.0) }
+        Self(saturate(self.0 * rhs.0))
     }
 }
 
@@ -228,11 +342,16 @@ impl std::ops::MulAssign for Unsigned {
     }
 }
 
+/// Saturates at the largest finite value.
+///
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Div for Unsigned {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 / rhs.0) }
+        Self(saturate(self.0 / divisor(rhs.0)))
     }
 }
 
@@ -242,11 +361,14 @@ impl std::ops::DivAssign for Unsigned {
     }
 }
 
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Rem for Unsigned {
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 % rhs.0) }
+        Self(self.0 % divisor(rhs.0))
     }
 }
 
@@ -257,18 +379,19 @@ impl std::ops::RemAssign for Unsigned {
 }
 
 impl std::ops::Neg for Unsigned {
-    type Output = Self;
+    type Output = Number;
 
     fn neg(self) -> Self::Output {
-        unsafe { Self::new_unchecked(-self.0) }
+        Number(-self.0)
     }
 }
 
+/// Saturates at the largest finite value.
 impl std::ops::Add<Positive> for Unsigned {
     type Output = Self;
 
     fn add(self, rhs: Positive) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 + rhs.0) }
+        Self(saturate(self.0 + rhs.0))
     }
 }
 
@@ -279,24 +402,19 @@ impl std::ops::AddAssign<Positive> for Unsigned {
 }
 
 impl std::ops::Sub<Positive> for Unsigned {
-    type Output = Self;
+    type Output = Number;
 
     fn sub(self, rhs: Positive) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 - rhs.0) }
-    }
-}
-
-impl std::ops::SubAssign<Positive> for Unsigned {
-    fn sub_assign(&mut self, rhs: Positive) {
-        *self = *self - rhs;
+        Number(self.0 - rhs.0)
     }
 }
 
+/// Saturates at the largest finite value.
 impl std::ops::Mul<Positive> for Unsigned {
     type Output = Self;
 
     fn mul(self, rhs: Positive) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 * rhs.0) }
+        Self(saturate(self.0 * rhs.0))
     }
 }
 
@@ -306,11 +424,12 @@ impl std::ops::MulAssign<Positive> for Unsigned {
     }
 }
 
+/// Saturates at the largest finite value.
 impl std::ops::Div<Positive> for Unsigned {
     type Output = Self;
 
     fn div(self, rhs: Positive) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 / rhs.0) }
+        Self(saturate(self.0 / rhs.0))
     }
 }
 
@@ -324,7 +443,7 @@ impl std::ops::Rem<Positive> for Unsigned {
     type Output = Self;
 
     fn rem(self, rhs: Positive) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 % rhs.0) }
+        Self(self.0 % rhs.0)
     }
 }
 
@@ -348,18 +467,27 @@ impl PartialOrd<Positive> for Unsigned {
 
 impl From<Positive> for Unsigned {
     fn from(value: Positive) -> Self {
-        unsafe { Self::new_unchecked(value.0) }
+        Self(value.0)
+    }
+}
+
+impl TryFrom<Number> for Unsigned {
+    type Error = UnsignedError;
+
+    fn try_from(value: Number) -> Result<Self, Self::Error> {
+        Self::new(value.0)
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "f32", into = "f32"))]
 pub struct Number(f32);
 
 impl Eq for Number {}
 
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() })
+        Some(self.cmp(other))
     }
 }
 
@@ -391,6 +519,9 @@ impl Number {
         Ok(Self(value))
     }
 
+    /// # Safety
+    ///
+    /// The value must pass the checks of [`Self::new`].
     pub unsafe fn new_unchecked(value: f32) -> Self {
         debug_assert!(Self::new(value).is_ok());
         Self(value)
@@ -403,21 +534,62 @@ impl Number {
     pub const fn one() -> Self {
         Self(1.0)
     }
+
+    /// Nearest valid value, zero for NaN.
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            return Self::zero();
+        }
+
+        Self(saturate(value))
+    }
+
+    /// Sum unless it overflows.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    /// Difference unless it overflows.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    /// Product unless it overflows.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 * rhs.0).ok()
+    }
+
+    /// Quotient unless the divisor is zero or the quotient overflows.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 / rhs.0).ok()
+    }
+
+    /// Remainder unless the divisor is zero.
+    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 % rhs.0).ok()
+    }
+}
+
+impl From<Unsigned> for Number {
+    fn from(value: Unsigned) -> Self {
+        Self(value.0)
+    }
 }
 
 impl std::ops::Neg for Number {
     type Output = Self;
 
     fn neg(self) -> Self::Output {
-        unsafe { Self::new_unchecked(-self.0) }
+        Self(-self.0)
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Add for Number {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 + rhs.0) }
+        Self(saturate(self.0 + rhs.0))
     }
 }
 
@@ -427,11 +599,12 @@ impl std::ops::AddAssign for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Sub for Number {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 - rhs.0) }
+        Self(saturate(self.0 - rhs.0))
     }
 }
 
@@ -441,11 +614,12 @@ impl std::ops::SubAssign for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Mul for Number {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 * rhs.0) }
+        Self(saturate(self.0 * rhs.0))
     }
 }
 
@@ -455,11 +629,16 @@ impl std::ops::MulAssign for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
+///
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Div for Number {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 / rhs.0) }
+        Self(saturate(self.0 / divisor(rhs.0)))
     }
 }
 
@@ -469,11 +648,14 @@ impl std::ops::DivAssign for Number {
     }
 }
 
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Rem for Number {
     type Output = Self;
 
     fn rem(self, rhs: Self) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 % rhs.0) }
+        Self(self.0 % divisor(rhs.0))
     }
 }
 
@@ -491,7 +673,7 @@ impl PartialEq<Number> for f32 {
 
 impl PartialOrd<Number> for f32 {
     fn partial_cmp(&self, other: &Number) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.partial_cmp(&other.0).unwrap_unchecked() })
+        self.partial_cmp(&other.0)
     }
 }
 
@@ -501,6 +683,14 @@ impl From<Number> for f32 {
     }
 }
 
+impl TryFrom<f32> for Number {
+    type Error = NumberError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
 impl std::ops::Add<Number> for f32 {
     type Output = Self;
 
@@ -612,13 +802,14 @@ impl std::ops::Rem<f32> for Number {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "f32", into = "f32"))]
 pub struct Positive(f32);
 
 impl Eq for Positive {}
 
 impl PartialOrd for Positive {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() })
+        Some(self.cmp(other))
     }
 }
 
@@ -657,6 +848,9 @@ impl Positive {
         Ok(Self(value))
     }
 
+    /// # Safety
+    ///
+    /// The value must pass the checks of [`Self::new`].
     pub unsafe fn new_unchecked(value: f32) -> Self {
         debug_assert!(Self::new(value).is_ok());
         Self(value)
@@ -665,6 +859,96 @@ impl Positive {
     pub const fn one() -> Self {
         Self(1.0)
     }
+
+    /// Nearest valid value, the smallest positive normal value for NaN.
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            return Self(f32::MIN_POSITIVE);
+        }
+
+        Self(value.clamp(f32::MIN_POSITIVE, f32::MAX))
+    }
+
+    /// Sum unless it overflows.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    /// Difference unless it is not positive.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    /// Product unless it overflows or underflows to zero.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 * rhs.0).ok()
+    }
+
+    /// Quotient unless it overflows or underflows to zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 / rhs.0).ok()
+    }
+}
+
+/// Saturates at the largest finite value.
+impl std::ops::Add for Positive {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(saturate(self.0 + rhs.0))
+    }
+}
+
+impl std::ops::AddAssign for Positive {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Positive {
+    type Output = Number;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Number(self.0 - rhs.0)
+    }
+}
+
+/// Clamped between the smallest positive normal value and the largest finite
+/// value.
+impl std::ops::Mul for Positive {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::clamped(self.0 * rhs.0)
+    }
+}
+
+impl std::ops::MulAssign for Positive {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+/// Clamped between the smallest positive normal value and the largest finite
+/// value.
+impl std::ops::Div for Positive {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::clamped(self.0 / rhs.0)
+    }
+}
+
+impl std::ops::DivAssign for Positive {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl From<Positive> for Number {
+    fn from(value: Positive) -> Self {
+        Self(value.0)
+    }
 }
 
 impl From<Positive> for f32 {
@@ -695,11 +979,12 @@ impl TryFrom<f64> for Positive {
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Add<Unsigned> for Number {
     type Output = Self;
 
     fn add(self, rhs: Unsigned) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 + rhs.0) }
+        Self(saturate(self.0 + rhs.0))
     }
 }
 
@@ -709,11 +994,12 @@ impl std::ops::AddAssign<Unsigned> for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Sub<Unsigned> for Number {
     type Output = Self;
 
     fn sub(self, rhs: Unsigned) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 - rhs.0) }
+        Self(saturate(self.0 - rhs.0))
     }
 }
 
@@ -723,11 +1009,12 @@ impl std::ops::SubAssign<Unsigned> for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
 impl std::ops::Mul<Unsigned> for Number {
     type Output = Self;
 
     fn mul(self, rhs: Unsigned) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 * rhs.0) }
+        Self(saturate(self.0 * rhs.0))
     }
 }
 
@@ -737,11 +1024,16 @@ impl std::ops::MulAssign<Unsigned> for Number {
     }
 }
 
+/// Saturates at the largest finite magnitude.
+///
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Div<Unsigned> for Number {
     type Output = Self;
 
     fn div(self, rhs: Unsigned) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 / rhs.0) }
+        Self(saturate(self.0 / divisor(rhs.0)))
     }
 }
 
@@ -751,11 +1043,14 @@ impl std::ops::DivAssign<Unsigned> for Number {
     }
 }
 
+/// # Panics
+///
+/// Panics if the divisor is zero.
 impl std::ops::Rem<Unsigned> for Number {
     type Output = Self;
 
     fn rem(self, rhs: Unsigned) -> Self::Output {
-        unsafe { Self::new_unchecked(self.0 % rhs.0) }
+        Self(self.0 % divisor(rhs.0))
     }
 }
 
@@ -773,7 +1068,7 @@ impl PartialEq<Unsigned> for f32 {
 
 impl PartialOrd<Unsigned> for f32 {
     fn partial_cmp(&self, other: &Unsigned) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.partial_cmp(&other.0).unwrap_unchecked() })
+        self.partial_cmp(&other.0)
     }
 }
 
@@ -848,13 +1143,14 @@ impl std::ops::RemAssign<Unsigned> for f32 {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(try_from = "f32", into = "f32"))]
 pub struct PercentUnsigned(f32);
 
 impl Eq for PercentUnsigned {}
 
 impl PartialOrd for PercentUnsigned {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(unsafe { self.0.partial_cmp(&other.0).unwrap_unchecked() })
+        Some(self.cmp(other))
     }
 }
 
@@ -900,6 +1196,9 @@ impl PercentUnsigned {
         Ok(Self(value))
     }
 
+    /// # Safety
+    ///
+    /// The value must pass the checks of [`Self::new`].
     pub unsafe fn new_unchecked(value: f32) -> Self {
         debug_assert!(Self::new(value).is_ok());
         Self(value)
@@ -912,4 +1211,778 @@ impl PercentUnsigned {
     pub const fn one() -> Self {
         Self(1.0)
     }
+
+    /// Nearest valid value, zero for NaN.
+    pub fn clamped(value: f32) -> Self {
+        if value.is_nan() {
+            return Self::zero();
+        }
+
+        Self(value.clamp(0.0, 1.0))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 + rhs.0).ok()
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::new(self.0 - rhs.0).ok()
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self::clamped(self.0 + rhs.0)
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::clamped(self.0 - rhs.0)
+    }
+}
+
+impl std::ops::Mul for PercentUnsigned {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl std::ops::MulAssign for PercentUnsigned {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl From<PercentUnsigned> for f32 {
+    fn from(value: PercentUnsigned) -> Self {
+        value.0
+    }
+}
+
+impl TryFrom<f32> for PercentUnsigned {
+    type Error = PercentUnsignedError;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size {
+    width: Unsigned,
+    height: Unsigned,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Size {
+    pub const fn new(width: Unsigned, height: Unsigned) -> Self {
+        Self { width, height }
+    }
+
+    pub const fn zero() -> Self {
+        Self::new(Unsigned::zero(), Unsigned::zero())
+    }
+
+    pub const fn width(&self) -> Unsigned {
+        self.width
+    }
+
+    pub fn widthf(&self) -> f32 {
+        self.width.into()
+    }
+
+    pub const fn height(&self) -> Unsigned {
+        self.height
+    }
+
+    pub fn heightf(&self) -> f32 {
+        self.height.into()
+    }
+
+    /// Whether the size has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width == Unsigned::zero() || self.height == Unsigned::zero()
+    }
+
+    pub fn to_vector(&self) -> Vector {
+        Vector::new(self.widthf(), self.heightf())
+    }
+}
+
+impl std::ops::Add for Size {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.width + rhs.width, self.height + rhs.height)
+    }
+}
+
+impl std::ops::AddAssign for Size {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Add<Insets> for Size {
+    type Output = Self;
+
+    /// Size of the content with the insets around it.
+    fn add(self, rhs: Insets) -> Self::Output {
+        Self::new(self.width + rhs.left + rhs.right, self.height + rhs.top + rhs.bottom)
+    }
+}
+
+impl std::ops::Mul<Unsigned> for Size {
+    type Output = Self;
+
+    fn mul(self, rhs: Unsigned) -> Self::Output {
+        Self::new(self.width * rhs, self.height * rhs)
+    }
+}
+
+impl std::ops::MulAssign<Unsigned> for Size {
+    fn mul_assign(&mut self, rhs: Unsigned) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::Div<Positive> for Size {
+    type Output = Self;
+
+    fn div(self, rhs: Positive) -> Self::Output {
+        Self::new(self.width / rhs, self.height / rhs)
+    }
+}
+
+impl std::ops::DivAssign<Positive> for Size {
+    fn div_assign(&mut self, rhs: Positive) {
+        *self = *self / rhs;
+    }
+}
+
+impl TryFrom<(f32, f32)> for Size {
+    type Error = UnsignedError;
+
+    fn try_from((width, height): (f32, f32)) -> Result<Self, Self::Error> {
+        Ok(Self::new(width.try_into()?, height.try_into()?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Rectangle of given size with the top left corner at the point.
+    pub fn sized(x: f32, y: f32, size: Size) -> Self {
+        Self::new(x, y, size.widthf(), size.heightf())
+    }
+
+    /// Smallest rectangle containing both points.
+    pub fn from_points(a: Point, b: Point) -> Self {
+        let x = a.x.min(b.x);
+        let y = a.y.min(b.y);
+        Self::new(x, y, a.x.max(b.x) - x, a.y.max(b.y) - y)
+    }
+
+    /// Smallest rectangle containing all the points, if there are any.
+    pub fn bounding(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), point| {
+            (Point::new(min.x.min(point.x), min.y.min(point.y)), Point::new(max.x.max(point.x), max.y.max(point.y)))
+        });
+        Some(Self::from_points(min, max))
+    }
+
+    pub fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+
+    pub fn offset(&self, x: f32, y: f32) -> Self {
+        Self::new(self.x + x, self.y + y, self.width, self.height)
+    }
+
+    pub fn left(&self) -> f32 {
+        self.x
+    }
+
+    pub fn top(&self) -> f32 {
+        self.y
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    /// Whether the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// Whether the point lies within the rectangle, including the top and left
+    /// edges but not the bottom and right ones.
+    pub fn contains_point(&self, point: Point) -> bool {
+        point.x >= self.left() && point.x < self.right() && point.y >= self.top() && point.y < self.bottom()
+    }
+
+    /// Whether the other rectangle lies entirely within this one.
+    pub fn contains(&self, other: &Rect) -> bool {
+        other.left() >= self.left()
+            && other.top() >= self.top()
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    /// Shrink the rectangle by given amount on each side. Width and height never
+    /// get below zero.
+    pub fn deflate(&self, top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            x: self.x + left,
+            y: self.y + top,
+            width: (self.width - left - right).max(0.0),
+            height: (self.height - top - bottom).max(0.0),
+        }
+    }
+
+    /// Grow the rectangle by given amount on each side.
+    pub fn inflate(&self, top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        Self {
+            x: self.x - left,
+            y: self.y - top,
+            width: self.width + left + right,
+            height: self.height + top + bottom,
+        }
+    }
+
+    /// Area shared by both rectangles if they overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Self> {
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if left < right && top < bottom {
+            Some(Self::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
+    /// How far the other rectangle reaches past each side of this one, zero
+    /// for the sides it does not reach past and for an empty rectangle.
+    pub fn overflow(&self, other: &Rect) -> Insets {
+        if other.is_empty() {
+            return Insets::zero();
+        }
+
+        Insets::trbl(
+            Unsigned::clamped(self.top() - other.top()),
+            Unsigned::clamped(other.right() - self.right()),
+            Unsigned::clamped(other.bottom() - self.bottom()),
+            Unsigned::clamped(self.left() - other.left()),
+        )
+    }
+
+    /// Smallest rectangle containing both rectangles. An empty rectangle adds
+    /// nothing to the other one.
+    pub fn union(&self, other: &Rect) -> Self {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Self::new(left, top, right - left, bottom - top)
+    }
+}
+
+impl std::ops::Add<Vector> for Rect {
+    type Output = Self;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        self.offset(rhs.x, rhs.y)
+    }
+}
+
+impl std::ops::AddAssign<Vector> for Rect {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub<Vector> for Rect {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        self.offset(-rhs.x, -rhs.y)
+    }
+}
+
+impl std::ops::SubAssign<Vector> for Rect {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Add<Insets> for Rect {
+    type Output = Self;
+
+    /// Grow the rectangle by the insets.
+    fn add(self, rhs: Insets) -> Self::Output {
+        self.inflate(rhs.top.into(), rhs.right.into(), rhs.bottom.into(), rhs.left.into())
+    }
+}
+
+impl std::ops::Sub<Insets> for Rect {
+    type Output = Self;
+
+    /// Shrink the rectangle by the insets.
+    fn sub(self, rhs: Insets) -> Self::Output {
+        self.deflate(rhs.top.into(), rhs.right.into(), rhs.bottom.into(), rhs.left.into())
+    }
+}
+
+/// Position in the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn origin() -> Self {
+        Self::new(0.0, 0.0)
+    }
+
+    /// Displacement from the origin to the point.
+    pub const fn to_vector(self) -> Vector {
+        Vector::new(self.x, self.y)
+    }
+}
+
+impl std::ops::Add<Vector> for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl std::ops::AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub<Vector> for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl std::ops::SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+/// Displacement in the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Vector {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    pub const fn zero() -> Self {
+        Self::new(0.0, 0.0)
+    }
+
+    pub fn length(&self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    pub const fn to_point(self) -> Point {
+        Point::new(self.x, self.y)
+    }
+}
+
+impl std::ops::Add for Vector {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl std::ops::AddAssign for Vector {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Vector {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl std::ops::SubAssign for Vector {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul<f32> for Vector {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vector {
+    fn mul_assign(&mut self, rhs: f32) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::ops::Div<f32> for Vector {
+    type Output = Self;
+
+    fn div(self, rhs: f32) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl std::ops::DivAssign<f32> for Vector {
+    fn div_assign(&mut self, rhs: f32) {
+        *self = *self / rhs;
+    }
+}
+
+impl std::ops::Neg for Vector {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// Space on each side of a rectangle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Insets {
+    pub top: Unsigned,
+    pub right: Unsigned,
+    pub bottom: Unsigned,
+    pub left: Unsigned,
+}
+
+impl Default for Insets {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Insets {
+    pub const fn trbl(top: Unsigned, right: Unsigned, bottom: Unsigned, left: Unsigned) -> Self {
+        Self { top, right, bottom, left }
+    }
+
+    pub const fn all(inset: Unsigned) -> Self {
+        Self::trbl(inset, inset, inset, inset)
+    }
+
+    pub const fn symmetric(vertical: Unsigned, horizontal: Unsigned) -> Self {
+        Self::trbl(vertical, horizontal, vertical, horizontal)
+    }
+
+    pub const fn zero() -> Self {
+        Self::all(Unsigned::zero())
+    }
+
+    /// Sum of the left and right insets.
+    pub fn horizontal(&self) -> f32 {
+        f32::from(self.left) + f32::from(self.right)
+    }
+
+    /// Sum of the top and bottom insets.
+    pub fn vertical(&self) -> f32 {
+        f32::from(self.top) + f32::from(self.bottom)
+    }
+
+    /// Same insets with left and right ones swapped.
+    pub const fn mirrored(self) -> Self {
+        Self::trbl(self.top, self.left, self.bottom, self.right)
+    }
+
+    /// Difference of each side, zero where it would be negative.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        Self::trbl(
+            self.top.saturating_sub(rhs.top),
+            self.right.saturating_sub(rhs.right),
+            self.bottom.saturating_sub(rhs.bottom),
+            self.left.saturating_sub(rhs.left),
+        )
+    }
+}
+
+impl std::ops::Add for Insets {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::trbl(self.top + rhs.top, self.right + rhs.right, self.bottom + rhs.bottom, self.left + rhs.left)
+    }
+}
+
+impl std::ops::AddAssign for Insets {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Mul<Unsigned> for Insets {
+    type Output = Self;
+
+    fn mul(self, rhs: Unsigned) -> Self::Output {
+        Self::trbl(self.top * rhs, self.right * rhs, self.bottom * rhs, self.left * rhs)
+    }
+}
+
+impl std::ops::MulAssign<Unsigned> for Insets {
+    fn mul_assign(&mut self, rhs: Unsigned) {
+        *self = *self * rhs;
+    }
+}
+
+/// Horizontal and vertical radius of each corner of a rounded rectangle, as the
+/// width and height of the size. Corners with either radius of zero are sharp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Radii {
+    pub top_left: Size,
+    pub top_right: Size,
+    pub bottom_right: Size,
+    pub bottom_left: Size,
+}
+
+impl Default for Radii {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Radii {
+    pub const fn new(top_left: Size, top_right: Size, bottom_right: Size, bottom_left: Size) -> Self {
+        Self { top_left, top_right, bottom_right, bottom_left }
+    }
+
+    /// Same elliptical radii for every corner.
+    pub const fn elliptical(radii: Size) -> Self {
+        Self::new(radii, radii, radii, radii)
+    }
+
+    /// Same circular radius for every corner.
+    pub const fn all(radius: Unsigned) -> Self {
+        Self::elliptical(Size::new(radius, radius))
+    }
+
+    pub const fn zero() -> Self {
+        Self::all(Unsigned::zero())
+    }
+
+    /// Whether every corner is sharp.
+    pub fn is_zero(&self) -> bool {
+        self.corners().iter().all(|corner| corner.widthf() <= 0.0 || corner.heightf() <= 0.0)
+    }
+
+    /// Top left, top right, bottom right and bottom left radii.
+    pub const fn corners(&self) -> [Size; 4] {
+        [self.top_left, self.top_right, self.bottom_right, self.bottom_left]
+    }
+
+    /// Radii reduced by the amount in both directions, such as for the inner
+    /// edge of a border of that width.
+    pub fn shrink(self, amount: f32) -> Self {
+        let [top_left, top_right, bottom_right, bottom_left] = self.corners().map(|corner| {
+            Size::new(Unsigned::clamped(corner.widthf() - amount), Unsigned::clamped(corner.heightf() - amount))
+        });
+        Self::new(top_left, top_right, bottom_right, bottom_left)
+    }
+
+    /// Radii scaled down proportionally so that the curves of adjacent corners
+    /// do not overlap on any side of a rectangle of the width and height.
+    pub fn fit(self, width: f32, height: f32) -> Self {
+        let (width, height) = (width.max(0.0), height.max(0.0));
+        let ratio = |side: f32, a: f32, b: f32| if a + b > side { side / (a + b) } else { 1.0 };
+        let scale = ratio(width, self.top_left.widthf(), self.top_right.widthf())
+            .min(ratio(width, self.bottom_left.widthf(), self.bottom_right.widthf()))
+            .min(ratio(height, self.top_left.heightf(), self.bottom_left.heightf()))
+            .min(ratio(height, self.top_right.heightf(), self.bottom_right.heightf()));
+        let [top_left, top_right, bottom_right, bottom_left] = self.corners().map(|corner| {
+            Size::new(Unsigned::clamped(corner.widthf() * scale), Unsigned::clamped(corner.heightf() * scale))
+        });
+        Self::new(top_left, top_right, bottom_right, bottom_left)
+    }
+}
+
+/// Affine transformation of the plane.
+///
+/// Coefficients are stored as `[a, b, c, d, e, f]` so that the point `(x, y)`
+/// is mapped to `(a * x + c * y + e, b * x + d * y + f)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Affine([f32; 6]);
+
+impl Default for Affine {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Affine {
+    pub const fn identity() -> Self {
+        Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+    }
+
+    pub const fn translate(x: f32, y: f32) -> Self {
+        Self([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    pub const fn scale(x: f32, y: f32) -> Self {
+        Self([x, 0.0, 0.0, y, 0.0, 0.0])
+    }
+
+    /// Rotation by the angle in radians, clockwise as the y axis points down.
+    pub fn rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    /// Skew by the angles in radians along the x and y axes.
+    pub fn skew(x: f32, y: f32) -> Self {
+        Self([1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0])
+    }
+
+    pub const fn from_coefficients(coefficients: [f32; 6]) -> Self {
+        Self(coefficients)
+    }
+
+    pub const fn coefficients(&self) -> [f32; 6] {
+        self.0
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// Transformation that applies `self` first and `next` after it.
+    pub fn then(&self, next: &Self) -> Self {
+        let [a, b, c, d, e, f] = self.0;
+        let [na, nb, nc, nd, ne, nf] = next.0;
+        Self([
+            na * a + nc * b,
+            nb * a + nd * b,
+            na * c + nc * d,
+            nb * c + nd * d,
+            na * e + nc * f + ne,
+            nb * e + nd * f + nf,
+        ])
+    }
+
+    /// Inverse transformation, if the transformation is not degenerate.
+    pub fn invert(&self) -> Option<Self> {
+        let [a, b, c, d, e, f] = self.0;
+        let det = a * d - b * c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+        Some(Self([
+            d * inv,
+            -b * inv,
+            -c * inv,
+            a * inv,
+            (c * f - d * e) * inv,
+            (b * e - a * f) * inv,
+        ]))
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.0;
+        (a * x + c * y + e, b * x + d * y + f)
+    }
+
+    pub fn transform_point(&self, point: Point) -> Point {
+        let (x, y) = self.apply(point.x, point.y);
+        Point::new(x, y)
+    }
+
+    /// Transform the displacement, which is not affected by translation.
+    pub fn transform_vector(&self, vector: Vector) -> Vector {
+        let [a, b, c, d, _, _] = self.0;
+        Vector::new(a * vector.x + c * vector.y, b * vector.x + d * vector.y)
+    }
+
+    /// Bounding box of the transformed rectangle.
+    pub fn transform_rect(&self, rect: &Rect) -> Rect {
+        let corners = [
+            Point::new(rect.left(), rect.top()),
+            Point::new(rect.right(), rect.top()),
+            Point::new(rect.right(), rect.bottom()),
+            Point::new(rect.left(), rect.bottom()),
+        ];
+        Rect::bounding(corners.map(|corner| self.transform_point(corner))).expect("rectangle has corners")
+    }
 }
@@ -1,14 +1,19 @@
 use crate::base::*;
+use crate::layout::{BoxConstraints, Context, Length};
 use crate::math::*;
+use crate::render::Canvas;
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stroke {
-    pub width: Positive,
+    pub width: Length,
     pub shader: Shader,
     pub cap: Cap,
     pub join: Join,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Cap {
     Flat,
     Round,
@@ -16,20 +21,139 @@ pub enum Cap {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Join {
     Miter(Unsigned),
     Round,
     Bevel,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verb {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo(f32, f32, f32, f32),
+    CubicTo(f32, f32, f32, f32, f32, f32),
+    Close,
+}
+
+/// Outline built from straight and curved segments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    verbs: Vec<Verb>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rect(rect: Rect) -> Self {
+        let mut path = Self::new();
+        path.move_to(rect.left(), rect.top());
+        path.line_to(rect.right(), rect.top());
+        path.line_to(rect.right(), rect.bottom());
+        path.line_to(rect.left(), rect.bottom());
+        path.close();
+        path
+    }
+
+    /// Rectangle with the corners rounded by elliptical arcs, with the radii
+    /// [fitted](Radii::fit) to the rectangle.
+    pub fn rounded_rect(rect: Rect, radii: Radii) -> Self {
+        if radii.is_zero() {
+            return Self::rect(rect);
+        }
+
+        // Distance of the control points from the ends of a quarter arc.
+        const KAPPA: f32 = 0.552_284_8;
+        let radii = radii.fit(rect.width, rect.height);
+        let [top_left, top_right, bottom_right, bottom_left] = radii.corners().map(|corner| {
+            match (corner.widthf(), corner.heightf()) {
+                (x, y) if x > 0.0 && y > 0.0 => (x, y),
+                _ => (0.0, 0.0),
+            }
+        });
+        let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+
+        let mut path = Self::new();
+        path.move_to(left + top_left.0, top);
+        path.line_to(right - top_right.0, top);
+        let (x, y) = top_right;
+        path.cubic_to(right - x * (1.0 - KAPPA), top, right, top + y * (1.0 - KAPPA), right, top + y);
+        path.line_to(right, bottom - bottom_right.1);
+        let (x, y) = bottom_right;
+        path.cubic_to(right, bottom - y * (1.0 - KAPPA), right - x * (1.0 - KAPPA), bottom, right - x, bottom);
+        path.line_to(left + bottom_left.0, bottom);
+        let (x, y) = bottom_left;
+        path.cubic_to(left + x * (1.0 - KAPPA), bottom, left, bottom - y * (1.0 - KAPPA), left, bottom - y);
+        path.line_to(left, top + top_left.1);
+        let (x, y) = top_left;
+        path.cubic_to(left, top + y * (1.0 - KAPPA), left + x * (1.0 - KAPPA), top, left + x, top);
+        path.close();
+        path
+    }
+
+    pub fn verbs(&self) -> &[Verb] {
+        &self.verbs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verbs.is_empty()
+    }
+
+    /// Smallest rectangle containing every point of the path, including the
+    /// control points of the curves.
+    pub fn bounds(&self) -> Option<Rect> {
+        let points = self.verbs.iter().flat_map(|verb| match *verb {
+            Verb::MoveTo(x, y) | Verb::LineTo(x, y) => vec![Point::new(x, y)],
+            Verb::QuadTo(cx, cy, x, y) => vec![Point::new(cx, cy), Point::new(x, y)],
+            Verb::CubicTo(c1x, c1y, c2x, c2y, x, y) => vec![Point::new(c1x, c1y), Point::new(c2x, c2y), Point::new(x, y)],
+            Verb::Close => vec![],
+        });
+        Rect::bounding(points)
+    }
+
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        self.verbs.push(Verb::MoveTo(x, y));
+    }
+
+    pub fn line_to(&mut self, x: f32, y: f32) {
+        self.verbs.push(Verb::LineTo(x, y));
+    }
+
+    pub fn quad_to(&mut self, cx: f32, cy: f32, x: f32, y: f32) {
+        self.verbs.push(Verb::QuadTo(cx, cy, x, y));
+    }
+
+    pub fn cubic_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
+        self.verbs.push(Verb::CubicTo(c1x, c1y, c2x, c2y, x, y));
+    }
+
+    pub fn close(&mut self) {
+        self.verbs.push(Verb::Close);
+    }
+}
+
 pub struct Border<E: Element> {
     element: E,
     stroke: Stroke,
+    width: f32,
+    radii: Radii,
 }
 
 impl<E: Element> Border<E> {
+    /// Percent width of the stroke is taken of the smaller of the maximum width
+    /// and height given by the parent.
     pub fn new(element: E, stroke: Stroke) -> Self {
-        Self { element, stroke }
+        Self { element, stroke, width: 0.0, radii: Radii::zero() }
+    }
+
+    /// Round the outer edge of the border with the radii. The stroke follows
+    /// the same outline as a [`Background`] with these radii.
+    pub fn radii(mut self, radii: Radii) -> Self {
+        self.radii = radii;
+        self
     }
 
     pub fn stroke(&self) -> &Stroke {
@@ -38,14 +162,40 @@ impl<E: Element> Border<E> {
 }
 
 impl<E: Element> Element for Border<E> {
-    fn width(&self) -> f32 {
-        let stroke_width: f32 = self.stroke.width.into();
-        self.element.width() + stroke_width * 2.0
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let base = constraints.max_width().min(constraints.max_height());
+        self.width = self.stroke.width.resolvef(context, base);
+        let stroke_width = self.width;
+        let child = self.element.layout(constraints.deflate(stroke_width * 2.0, stroke_width * 2.0), context);
+        constraints.constrain_min(child.widthf() + stroke_width * 2.0, child.heightf() + stroke_width * 2.0)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        let stroke_width = self.width;
+        self.element.baseline().map(|baseline| stroke_width + baseline)
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        let stroke_width = self.width;
+        let inner = rect.deflate(stroke_width, stroke_width, stroke_width, stroke_width);
+        (rect.contains_point(point) && !inner.contains_point(point)) || self.element.hit_test(inner, point)
     }
 
-    fn height(&self) -> f32 {
-        let stroke_width: f32 = self.stroke.width.into();
-        self.element.height() + stroke_width * 2.0
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow().saturating_sub(Insets::all(Unsigned::clamped(self.width)))
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let stroke_width = self.width;
+
+        // Stroke is centered on the path so move it inside by half of the width.
+        let half = stroke_width / 2.0;
+        let outline = rect.deflate(half, half, half, half);
+        let radii = self.radii.fit(rect.width, rect.height).shrink(half);
+        canvas.stroke_path(&Path::rounded_rect(outline, radii), &self.stroke, stroke_width);
+
+        let inner = rect.deflate(stroke_width, stroke_width, stroke_width, stroke_width);
+        self.element.paint(canvas, inner);
     }
 }
 
@@ -53,4 +203,195 @@ impl<E: Element> Layer<E> for Border<E> {
     fn inner(&self) -> &E {
         &self.element
     }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Fills the rectangle of the element with the shader and paints the element
+/// on top. Wrap a [`Border`] with the same radii to stroke along the edge.
+pub struct Background<E: Element> {
+    element: E,
+    shader: Shader,
+    radii: Radii,
+}
+
+impl<E: Element> Background<E> {
+    pub fn new(element: E, shader: impl Into<Shader>) -> Self {
+        Self { element, shader: shader.into(), radii: Radii::zero() }
+    }
+
+    pub fn radii(mut self, radii: Radii) -> Self {
+        self.radii = radii;
+        self
+    }
+
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+}
+
+impl<E: Element> Element for Background<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        rect.contains_point(point) || self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        self.element.ink_overflow()
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        canvas.fill_path(&Path::rounded_rect(rect, self.radii), &self.shader);
+        self.element.paint(canvas, rect);
+    }
+}
+
+impl<E: Element> Layer<E> for Background<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
+
+/// Shadow of a rounded rectangle, blurred with the gaussian whose standard
+/// deviation is half of the blur radius as in CSS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoxShadow {
+    pub offset: Vector,
+    pub blur: Unsigned,
+    /// Distance the shape casting the shadow grows by before the blur, or
+    /// shrinks by if negative.
+    pub spread: Number,
+    pub color: Color,
+    /// Whether the shadow falls inside of the rectangle instead of outside.
+    pub inset: bool,
+}
+
+impl BoxShadow {
+    pub const fn new(offset: Vector, blur: Unsigned, color: Color) -> Self {
+        Self { offset, blur, spread: Number::zero(), color, inset: false }
+    }
+
+    pub fn spread(mut self, spread: Number) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn inset(mut self, inset: bool) -> Self {
+        self.inset = inset;
+        self
+    }
+
+    /// Rectangle and radii of the shape casting the shadow of the rounded
+    /// rectangle, before the blur. Inset shadows are cast by the outside of
+    /// the shape.
+    pub fn shape(&self, rect: Rect, radii: Radii) -> (Rect, Radii) {
+        let grow = if self.inset { -f32::from(self.spread) } else { f32::from(self.spread) };
+        let shape = rect.inflate(grow, grow, grow, grow).offset(self.offset.x, self.offset.y);
+        let shape = Rect::new(shape.x, shape.y, shape.width.max(0.0), shape.height.max(0.0));
+
+        // Sharp corners stay sharp.
+        let [top_left, top_right, bottom_right, bottom_left] = radii.fit(rect.width, rect.height).corners().map(|corner| {
+            if corner.widthf() > 0.0 && corner.heightf() > 0.0 {
+                Size::new(Unsigned::clamped(corner.widthf() + grow), Unsigned::clamped(corner.heightf() + grow))
+            } else {
+                Size::zero()
+            }
+        });
+        (shape, Radii::new(top_left, top_right, bottom_right, bottom_left))
+    }
+
+    /// Space the shadow covers beyond each side of the rectangle, up to three
+    /// standard deviations of the blur. Inset shadows cover none.
+    pub fn extents(&self) -> Insets {
+        if self.inset {
+            return Insets::zero();
+        }
+
+        let reach = f32::from(self.spread) + f32::from(self.blur) * 1.5;
+        let Vector { x, y } = self.offset;
+        Insets::trbl(
+            Unsigned::clamped(reach - y),
+            Unsigned::clamped(reach + x),
+            Unsigned::clamped(reach + y),
+            Unsigned::clamped(reach - x),
+        )
+    }
+}
+
+/// Paints the shadows of the rounded rectangle of the element below it, the
+/// first one on top. Outer shadows are reported as ink overflow and do not
+/// change the size of the element. Inset shadows are painted below the element
+/// too, so wrap a [`Background`] around the shadow to have them shade it.
+pub struct Shadow<E: Element> {
+    element: E,
+    shadows: Vec<BoxShadow>,
+    radii: Radii,
+}
+
+impl<E: Element> Shadow<E> {
+    pub fn new(element: E, shadows: impl Into<Vec<BoxShadow>>) -> Self {
+        Self { element, shadows: shadows.into(), radii: Radii::zero() }
+    }
+
+    pub fn radii(mut self, radii: Radii) -> Self {
+        self.radii = radii;
+        self
+    }
+
+    pub fn shadows(&self) -> &[BoxShadow] {
+        &self.shadows
+    }
+}
+
+impl<E: Element> Element for Shadow<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.element.layout(constraints, context)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        self.element.hit_test(rect, point)
+    }
+
+    fn ink_overflow(&self) -> Insets {
+        let max = |a: Unsigned, b: Unsigned| a.max(b);
+        self.shadows.iter().map(BoxShadow::extents).fold(self.element.ink_overflow(), |a, b| {
+            Insets::trbl(max(a.top, b.top), max(a.right, b.right), max(a.bottom, b.bottom), max(a.left, b.left))
+        })
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let (inset, outer): (Vec<_>, Vec<_>) = self.shadows.iter().partition(|shadow| shadow.inset);
+        for shadow in outer.into_iter().rev().chain(inset.into_iter().rev()) {
+            canvas.draw_shadow(rect, self.radii, shadow);
+        }
+        self.element.paint(canvas, rect);
+    }
+}
+
+impl<E: Element> Layer<E> for Shadow<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
 }
@@ -0,0 +1,56 @@
+use crate::base::*;
+use crate::blend::BlendMode;
+use crate::image::Image;
+use crate::math::*;
+use crate::path::{BoxShadow, Path, Stroke};
+use crate::text::Style;
+
+pub mod femtovg;
+pub mod snapshot;
+pub mod software;
+
+/// Drawing surface the elements paint themselves onto.
+///
+/// All coordinates are in logical pixels of the current coordinate system,
+/// that is after applying every transform pushed with [`Canvas::push_transform`].
+/// Clips, transforms and layers form a single stack: every `push_*` call must
+/// be matched with the corresponding `pop_*` call in reverse order.
+pub trait Canvas {
+    fn fill_rect(&mut self, rect: Rect, shader: &Shader);
+
+    fn fill_path(&mut self, path: &Path, shader: &Shader);
+
+    /// Stroke the path with the width in logical pixels, which the element
+    /// resolves from the width of the stroke during layout.
+    fn stroke_path(&mut self, path: &Path, stroke: &Stroke, width: f32);
+
+    /// Draw the shadow of the rounded rectangle, only outside of the rectangle
+    /// or only inside of it if the shadow is inset.
+    fn draw_shadow(&mut self, rect: Rect, radii: Radii, shadow: &BoxShadow);
+
+    /// Draw a single line of text so that the top of its line box is at the
+    /// top of `rect`. Text is not wrapped nor clipped to the rectangle.
+    fn draw_text(&mut self, text: &str, style: &Style, rect: Rect);
+
+    /// Draw the image stretched to fill the rectangle.
+    fn draw_image(&mut self, image: &Image, rect: Rect);
+
+    /// Restrict all subsequent drawing to the intersection of the current clip
+    /// and the given rectangle.
+    fn push_clip(&mut self, rect: Rect);
+
+    fn pop_clip(&mut self);
+
+    /// Apply the transformation to all subsequent drawing, on top of the
+    /// current one.
+    fn push_transform(&mut self, transform: Affine);
+
+    fn pop_transform(&mut self);
+
+    /// Redirect all subsequent drawing into a new transparent layer, which is
+    /// composited onto the content below with the opacity and the blend mode
+    /// within the current clip when popped.
+    fn push_layer(&mut self, opacity: PercentUnsigned, mode: BlendMode);
+
+    fn pop_layer(&mut self);
+}
@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use ::femtovg::imgref::Img;
+use ::femtovg::rgb::FromSlice;
+use ::femtovg::{
+    BlendFactor, Baseline, CompositeOperation, FontId, ImageFlags, ImageId, ImageSource, LineCap, LineJoin, Paint, PixelFormat,
+    RenderTarget, Renderer, Solidity,
+};
+use crate::base::*;
+use crate::blend::BlendMode;
+use crate::image::Image;
+use crate::math::*;
+use crate::path::{BoxShadow, Cap, Join, Path, Stroke, Verb};
+use crate::render::Canvas;
+use crate::text::{Glyph, Style};
+
+/// Largest number of repetitions of a gradient unrolled into the stops of a
+/// single femtovg gradient.
+const MAX_PERIODS: f32 = 64.0;
+
+/// Largest width and height of the texture a conic gradient is rendered into.
+const MAX_CONIC_SIZE: f32 = 256.0;
+
+/// GPU backend drawing onto a [femtovg::Canvas](::femtovg::Canvas).
+///
+/// Fonts and images are uploaded on first use and cached for subsequent frames.
+/// Gradients that femtovg does not support are emulated: repeated and reflected
+/// gradients are unrolled over the shape and conic ones are rendered into
+/// textures released on [flush](Self::flush).
+///
+/// Layers are rendered into textures of the size of the canvas. Porter-Duff
+/// operators and the screen blend mode are composited exactly, the multiply
+/// blend mode only over opaque content, and the other blend modes fall back to
+/// source over with a warning logged once per mode.
+pub struct FemtovgCanvas<R: Renderer> {
+    canvas: ::femtovg::Canvas<R>,
+    fonts: HashMap<u64, FontId>,
+    images: HashMap<u64, ImageId>,
+    patterns: HashMap<u64, ImageId>,
+    transient: Vec<ImageId>,
+    /// Textures of the pushed layers, if they could be created, with their
+    /// opacity and blend mode.
+    layers: Vec<(Option<ImageId>, PercentUnsigned, BlendMode)>,
+}
+
+impl<R: Renderer> FemtovgCanvas<R> {
+    pub fn new(canvas: ::femtovg::Canvas<R>) -> Self {
+        Self {
+            canvas,
+            fonts: HashMap::new(),
+            images: HashMap::new(),
+            patterns: HashMap::new(),
+            transient: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn inner(&self) -> &::femtovg::Canvas<R> {
+        &self.canvas
+    }
+
+    pub fn inner_mut(&mut self) -> &mut ::femtovg::Canvas<R> {
+        &mut self.canvas
+    }
+
+    pub fn into_inner(self) -> ::femtovg::Canvas<R> {
+        self.canvas
+    }
+
+    pub fn flush(&mut self) {
+        self.canvas.flush();
+        for id in self.transient.drain(..) {
+            self.canvas.delete_image(id);
+        }
+    }
+
+    /// Free the textures uploaded for the image, if any.
+    pub fn release_image(&mut self, image: &Image) {
+        for id in [self.images.remove(&image.id()), self.patterns.remove(&image.id())].into_iter().flatten() {
+            self.canvas.delete_image(id);
+        }
+    }
+
+    fn font(&mut self, style: &Style) -> Option<FontId> {
+        let font = style.resolve_font()?;
+        if let Some(id) = self.fonts.get(&font.id()) {
+            return Some(*id);
+        }
+
+        match self.canvas.add_font_mem(font.data()) {
+            Ok(id) => {
+                self.fonts.insert(font.id(), id);
+                Some(id)
+            }
+            Err(e) => {
+                log::warn!("failed to load font `{}`: {e}", style.font);
+                None
+            }
+        }
+    }
+
+    fn image(&mut self, image: &Image) -> Option<ImageId> {
+        upload(&mut self.canvas, &mut self.images, image, ImageFlags::empty())
+    }
+
+    fn pattern(&mut self, image: &Image) -> Option<ImageId> {
+        upload(&mut self.canvas, &mut self.patterns, image, ImageFlags::REPEAT_X | ImageFlags::REPEAT_Y)
+    }
+
+    /// Paint of the shader for a shape within the bounds.
+    fn paint(&mut self, shader: &Shader, bounds: Rect) -> Paint {
+        match shader {
+            Shader::Solid(solid) => Paint::color(color(*solid)),
+            Shader::Linear { start, end, gradient } => {
+                let axis = *end - *start;
+                let length = axis.x * axis.x + axis.y * axis.y;
+                let offsets = corners(bounds).map(|corner| {
+                    let offset = corner - *start;
+                    if length > 0.0 { (offset.x * axis.x + offset.y * axis.y) / length } else { 0.0 }
+                });
+                let (first, last, stops) = unroll(gradient, min(offsets), max(offsets));
+                let (from, to) = (*start + axis * first, *start + axis * last);
+                Paint::linear_gradient_stops(from.x, from.y, to.x, to.y, stops)
+            }
+            Shader::Radial { center, radius, gradient } => {
+                let radius: f32 = (*radius).into();
+                let distances = corners(bounds).map(|corner| (corner - *center).length() / radius);
+                let (_, last, stops) = unroll(gradient, 0.0, max(distances));
+                Paint::radial_gradient_stops(center.x, center.y, 0.0, radius * last, stops)
+            }
+            Shader::Conic { center, angle, gradient } => self.conic(*center, (*angle).into(), gradient, bounds),
+            Shader::Pattern { image, rect } => match self.pattern(image) {
+                Some(id) => Paint::image(id, rect.x, rect.y, rect.width, rect.height, 0.0, 1.0),
+                None => Paint::color(::femtovg::Color::rgbaf(0.0, 0.0, 0.0, 0.0)),
+            },
+        }
+    }
+
+    /// Conic gradient rendered into a texture covering the bounds.
+    fn conic(&mut self, center: Point, angle: f32, gradient: &Gradient, bounds: Rect) -> Paint {
+        let transparent = Paint::color(::femtovg::Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+        if bounds.is_empty() {
+            return transparent;
+        }
+
+        let width = bounds.width.ceil().min(MAX_CONIC_SIZE) as usize;
+        let height = bounds.height.ceil().min(MAX_CONIC_SIZE) as usize;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let point = Point::new(
+                    bounds.x + (x as f32 + 0.5) / width as f32 * bounds.width,
+                    bounds.y + (y as f32 + 0.5) / height as f32 * bounds.height,
+                );
+                let offset = point - center;
+                let turn = (offset.y.atan2(offset.x) - angle) / std::f32::consts::TAU;
+                let sampled = gradient.color_at(turn.rem_euclid(1.0));
+                let channel = |value: PercentUnsigned| (f32::from(value) * 255.0).round() as u8;
+                pixels.push(::femtovg::rgb::RGBA8::new(channel(sampled.red), channel(sampled.green), channel(sampled.blue), channel(sampled.alpha)));
+            }
+        }
+
+        match self.canvas.create_image(ImageSource::Rgba(Img::new(&pixels, width, height)), ImageFlags::empty()) {
+            Ok(id) => {
+                self.transient.push(id);
+                Paint::image(id, bounds.x, bounds.y, bounds.width, bounds.height, 0.0, 1.0)
+            }
+            Err(e) => {
+                log::warn!("failed to upload conic gradient: {e}");
+                transparent
+            }
+        }
+    }
+}
+
+fn upload<R: Renderer>(
+    canvas: &mut ::femtovg::Canvas<R>,
+    cache: &mut HashMap<u64, ImageId>,
+    image: &Image,
+    flags: ImageFlags,
+) -> Option<ImageId> {
+    if let Some(id) = cache.get(&image.id()) {
+        return Some(*id);
+    }
+
+    let pixels = Img::new(
+        image.pixels().as_rgba(),
+        image.pixel_width() as usize,
+        image.pixel_height() as usize,
+    );
+    match canvas.create_image(ImageSource::Rgba(pixels), flags) {
+        Ok(id) => {
+            cache.insert(image.id(), id);
+            Some(id)
+        }
+        Err(e) => {
+            log::warn!("failed to upload image: {e}");
+            None
+        }
+    }
+}
+
+fn color(color: Color) -> ::femtovg::Color {
+    ::femtovg::Color::rgbaf(color.red.into(), color.green.into(), color.blue.into(), color.alpha.into())
+}
+
+fn corners(rect: Rect) -> [Point; 4] {
+    [
+        Point::new(rect.left(), rect.top()),
+        Point::new(rect.right(), rect.top()),
+        Point::new(rect.right(), rect.bottom()),
+        Point::new(rect.left(), rect.bottom()),
+    ]
+}
+
+fn min(values: [f32; 4]) -> f32 {
+    values.into_iter().fold(f32::INFINITY, f32::min)
+}
+
+fn max(values: [f32; 4]) -> f32 {
+    values.into_iter().fold(f32::NEG_INFINITY, f32::max)
+}
+
+/// Stops of the gradient repeated over the offsets from `from` to `to`, with
+/// the offsets where the unrolled gradient starts and ends. Padded gradients
+/// are not unrolled.
+fn unroll(gradient: &Gradient, from: f32, to: f32) -> (f32, f32, Vec<(f32, ::femtovg::Color)>) {
+    let stop = |stop: &ColorStop| (f32::from(stop.position), color(stop.color));
+    if gradient.spread_mode() == Spread::Pad || !(from.is_finite() && to.is_finite()) {
+        return (0.0, 1.0, gradient.stops().iter().map(stop).collect());
+    }
+
+    let first = from.floor();
+    let last = to.ceil().max(first + 1.0).min(first + MAX_PERIODS);
+    let periods = last - first;
+    let mut stops = Vec::new();
+    for period in 0..periods as i32 {
+        let start = first + period as f32;
+        let reflected = gradient.spread_mode() == Spread::Reflect && start.rem_euclid(2.0) == 1.0;
+        let period_stops: Vec<_> = if reflected {
+            gradient.stops().iter().rev().map(|s| (1.0 - f32::from(s.position), color(s.color))).collect()
+        } else {
+            gradient.stops().iter().map(stop).collect()
+        };
+        stops.extend(period_stops.into_iter().map(|(position, color)| ((period as f32 + position) / periods, color)));
+    }
+    (first, last, stops)
+}
+
+fn path(path: &Path) -> ::femtovg::Path {
+    let mut result = ::femtovg::Path::new();
+    append(&mut result, path);
+    result
+}
+
+fn append(result: &mut ::femtovg::Path, path: &Path) {
+    for verb in path.verbs() {
+        match *verb {
+            Verb::MoveTo(x, y) => result.move_to(x, y),
+            Verb::LineTo(x, y) => result.line_to(x, y),
+            Verb::QuadTo(cx, cy, x, y) => result.quad_to(cx, cy, x, y),
+            Verb::CubicTo(c1x, c1y, c2x, c2y, x, y) => result.bezier_to(c1x, c1y, c2x, c2y, x, y),
+            Verb::Close => result.close(),
+        }
+    }
+}
+
+/// Set the composite operation of the current state to the blend mode.
+fn composite<R: Renderer>(canvas: &mut ::femtovg::Canvas<R>, mode: BlendMode) {
+    let operation = match mode {
+        BlendMode::Clear => return canvas.global_composite_blend_func(BlendFactor::Zero, BlendFactor::Zero),
+        BlendMode::Destination => return canvas.global_composite_blend_func(BlendFactor::Zero, BlendFactor::One),
+        BlendMode::Multiply => return canvas.global_composite_blend_func(BlendFactor::DstColor, BlendFactor::OneMinusSrcAlpha),
+        BlendMode::Screen => return canvas.global_composite_blend_func(BlendFactor::One, BlendFactor::OneMinusSrcColor),
+        BlendMode::Source => CompositeOperation::Copy,
+        BlendMode::SourceOver => CompositeOperation::SourceOver,
+        BlendMode::DestinationOver => CompositeOperation::DestinationOver,
+        BlendMode::SourceIn => CompositeOperation::SourceIn,
+        BlendMode::DestinationIn => CompositeOperation::DestinationIn,
+        BlendMode::SourceOut => CompositeOperation::SourceOut,
+        BlendMode::DestinationOut => CompositeOperation::DestinationOut,
+        BlendMode::SourceAtop => CompositeOperation::Atop,
+        BlendMode::DestinationAtop => CompositeOperation::DestinationAtop,
+        BlendMode::Xor => CompositeOperation::Xor,
+        BlendMode::Plus => CompositeOperation::Lighter,
+        mode => {
+            // Layers are composited every frame, so each mode is reported once.
+            static WARNED: AtomicU32 = AtomicU32::new(0);
+            let bit = 1 << mode as u32;
+            if WARNED.fetch_or(bit, Ordering::Relaxed) & bit == 0 {
+                log::warn!("{mode:?} blend mode is not supported, falling back to source over");
+            }
+            CompositeOperation::SourceOver
+        }
+    };
+    canvas.global_composite_operation(operation);
+}
+
+fn stroke_paint(mut paint: Paint, stroke: &Stroke, width: f32) -> Paint {
+    paint.set_line_width(width);
+    paint.set_line_cap(match stroke.cap {
+        Cap::Flat => LineCap::Butt,
+        Cap::Round => LineCap::Round,
+        Cap::Square => LineCap::Square,
+    });
+    match stroke.join {
+        Join::Miter(limit) => {
+            paint.set_line_join(LineJoin::Miter);
+            paint.set_miter_limit(limit.into());
+        }
+        Join::Round => paint.set_line_join(LineJoin::Round),
+        Join::Bevel => paint.set_line_join(LineJoin::Bevel),
+    }
+    paint
+}
+
+impl<R: Renderer> Canvas for FemtovgCanvas<R> {
+    fn fill_rect(&mut self, rect: Rect, shader: &Shader) {
+        let mut path = ::femtovg::Path::new();
+        path.rect(rect.x, rect.y, rect.width, rect.height);
+        let paint = self.paint(shader, rect);
+        self.canvas.fill_path(&path, &paint);
+    }
+
+    fn fill_path(&mut self, path: &Path, shader: &Shader) {
+        let paint = self.paint(shader, path.bounds().unwrap_or_default());
+        self.canvas.fill_path(&self::path(path), &paint);
+    }
+
+    fn stroke_path(&mut self, path: &Path, stroke: &Stroke, width: f32) {
+        // Miter joins reach at most half of the width times the limit past the path.
+        let reach = match stroke.join {
+            Join::Miter(limit) => f32::from(limit).max(1.0),
+            Join::Round | Join::Bevel => 1.0,
+        } * width / 2.0;
+        let bounds = path.bounds().unwrap_or_default().inflate(reach, reach, reach, reach);
+        let paint = stroke_paint(self.paint(&stroke.shader, bounds), stroke, width);
+        self.canvas.stroke_path(&self::path(path), &paint);
+    }
+
+    fn draw_shadow(&mut self, rect: Rect, radii: Radii, shadow: &BoxShadow) {
+        let (shape, shape_radii) = shadow.shape(rect, radii);
+        // Box gradients have a single radius, so the largest corner is used
+        // for all of them. The gradient spans the blur on both sides of the edge.
+        let radius = shape_radii.corners().iter().map(|corner| corner.widthf().min(corner.heightf())).fold(0.0, f32::max);
+        let feather = (f32::from(shadow.blur) * 2.0).max(1.0);
+        let (inner, outer) = match shadow.inset {
+            true => (::femtovg::Color::rgba(0, 0, 0, 0), color(shadow.color)),
+            false => (color(shadow.color), ::femtovg::Color::rgba(0, 0, 0, 0)),
+        };
+        let paint = Paint::box_gradient(shape.x, shape.y, shape.width, shape.height, radius, feather, inner, outer);
+
+        let outline = Path::rounded_rect(rect, radii);
+        let area = if shadow.inset {
+            path(&outline)
+        } else {
+            // Surroundings of the rectangle reached by the shadow, with the
+            // rectangle itself cut out.
+            let extents = shadow.extents();
+            let bounds = rect.inflate(extents.top.into(), extents.right.into(), extents.bottom.into(), extents.left.into());
+            let mut area = ::femtovg::Path::new();
+            area.rect(bounds.x, bounds.y, bounds.width, bounds.height);
+            append(&mut area, &outline);
+            area.solidity(Solidity::Hole);
+            area
+        };
+        self.canvas.fill_path(&area, &paint);
+    }
+
+    fn draw_text(&mut self, text: &str, style: &Style, rect: Rect) {
+        let Some(id) = self.font(style) else { return };
+        let Some(font) = style.resolve_font() else { return };
+        let metrics = font.metrics(style.size);
+
+        let bounds = Rect::new(rect.x, rect.y, font.measure(text, style), metrics.line_height());
+        let mut paint = self.paint(&style.shader, bounds);
+        paint.set_font(&[id]);
+        paint.set_font_size(style.size.into());
+        paint.set_text_baseline(Baseline::Alphabetic);
+
+        // Runs of glyphs between spaces are drawn at once so that femtovg shapes
+        // them the same way, at the positions the text is measured with. Spaces
+        // only advance by the word spacing and letter spacing is spread evenly
+        // between the glyphs of a run.
+        let glyphs = font.shape(text, style);
+        let blank = |glyph: &Glyph| text[glyph.cluster..].starts_with(char::is_whitespace);
+        let letter_spacing: f32 = style.letter_spacing.into();
+        let baseline = rect.y + metrics.ascent;
+        let mut x = rect.x;
+        let mut start = 0;
+        while start < glyphs.len() {
+            if blank(&glyphs[start]) {
+                x += glyphs[start].advance;
+                start += 1;
+                continue;
+            }
+
+            let end = glyphs[start..].iter().position(blank).map_or(glyphs.len(), |n| start + n);
+            let run = &glyphs[start..end];
+            let width: f32 = run.iter().map(|glyph| glyph.advance).sum();
+
+            // Glyphs are in visual order, so the characters of the run are the
+            // ones from its first cluster up to the cluster following its last.
+            let first = run.iter().map(|glyph| glyph.cluster).min().unwrap_or(0);
+            let last = run.iter().map(|glyph| glyph.cluster).max().unwrap_or(0);
+            let next = glyphs.iter().map(|glyph| glyph.cluster).filter(|&cluster| cluster > last).min().unwrap_or(text.len());
+
+            paint.set_letter_spacing((width - width / letter_spacing) / run.len() as f32);
+            if let Err(e) = self.canvas.fill_text(x, baseline, &text[first..next], &paint) {
+                log::warn!("failed to draw text: {e}");
+                return;
+            }
+            x += width;
+            start = end;
+        }
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: Rect) {
+        let Some(id) = self.image(image) else { return };
+        let paint = Paint::image(id, rect.x, rect.y, rect.width, rect.height, 0.0, 1.0);
+
+        let mut path = ::femtovg::Path::new();
+        path.rect(rect.x, rect.y, rect.width, rect.height);
+        self.canvas.fill_path(&path, &paint);
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        self.canvas.save();
+        self.canvas.intersect_scissor(rect.x, rect.y, rect.width, rect.height);
+    }
+
+    fn pop_clip(&mut self) {
+        self.canvas.restore();
+    }
+
+    fn push_transform(&mut self, transform: Affine) {
+        self.canvas.save();
+        self.canvas.set_transform(&::femtovg::Transform2D(transform.coefficients()));
+    }
+
+    fn pop_transform(&mut self) {
+        self.canvas.restore();
+    }
+
+    fn push_layer(&mut self, opacity: PercentUnsigned, mode: BlendMode) {
+        let (width, height) = (self.canvas.width() as usize, self.canvas.height() as usize);
+        let flags = ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED;
+        let id = match self.canvas.create_image_empty(width, height, PixelFormat::Rgba8, flags) {
+            Ok(id) => {
+                self.canvas.set_render_target(RenderTarget::Image(id));
+                self.canvas.clear_rect(0, 0, width as u32, height as u32, ::femtovg::Color::rgba(0, 0, 0, 0));
+                Some(id)
+            }
+            Err(e) => {
+                log::warn!("failed to create a layer, drawing directly: {e}");
+                None
+            }
+        };
+        self.layers.push((id, opacity, mode));
+    }
+
+    fn pop_layer(&mut self) {
+        let Some((id, opacity, mode)) = self.layers.pop() else {
+            panic!("pop_layer does not match the last push");
+        };
+        let Some(id) = id else { return };
+
+        let below = self.layers.iter().rev().find_map(|(id, _, _)| *id);
+        self.canvas.set_render_target(below.map_or(RenderTarget::Screen, RenderTarget::Image));
+
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        self.canvas.save();
+        self.canvas.reset_transform();
+        composite(&mut self.canvas, mode);
+        let mut path = ::femtovg::Path::new();
+        path.rect(0.0, 0.0, width, height);
+        self.canvas.fill_path(&path, &Paint::image(id, 0.0, 0.0, width, height, 0.0, opacity.into()));
+        self.canvas.restore();
+
+        // Drawing is deferred until the flush, so the texture must outlive it.
+        self.transient.push(id);
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use crate::base::*;
+use crate::math::*;
+use crate::render::software::render;
+
+/// Environment variable which, when set to anything but `0`, makes snapshots
+/// overwrite their reference images instead of comparing against them.
+pub const BLESS_VAR: &str = "HARUHI_BLESS";
+
+/// Golden-image test of an element rendered by the software backend.
+///
+/// The element is rendered at the given logical size and scale factor and compared
+/// against the reference PNG. On mismatch the rendered image is written next to
+/// the reference with `actual.png` extension along with `diff.png` which marks
+/// mismatching pixels in red over the faded reference.
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot {
+    size: Size,
+    scale: Positive,
+    tolerance: u8,
+}
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("reference image {0} does not exist, run with {BLESS_VAR}=1 to create it")]
+    MissingReference(PathBuf),
+
+    #[error("rendered image is {actual:?} while the reference is {expected:?}")]
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+
+    #[error("{pixels} pixels differ from the reference, see {diff}")]
+    Mismatch {
+        pixels: usize,
+        diff: PathBuf,
+    },
+
+    #[error("failed to read reference image: {0}")]
+    Decoding(#[from] png::DecodingError),
+
+    #[error("failed to write image: {0}")]
+    Encoding(#[from] png::EncodingError),
+}
+
+impl Snapshot {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            scale: Positive::one(),
+            tolerance: 0,
+        }
+    }
+
+    pub fn scale(mut self, scale: Positive) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Maximum allowed difference of any channel of a pixel.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn compare<E: Element + ?Sized>(&self, element: &mut E, reference: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let reference = reference.as_ref();
+        let actual = render(element, self.size, self.scale);
+
+        if blessing() {
+            if let Some(parent) = reference.parent() {
+                std::fs::create_dir_all(parent).map_err(png::EncodingError::from)?;
+            }
+            actual.write_png(reference)?;
+            return Ok(());
+        }
+
+        if !reference.exists() {
+            return Err(SnapshotError::MissingReference(reference.to_owned()));
+        }
+
+        let (width, height, expected) = read_png(reference)?;
+        if (width, height) != (actual.width(), actual.height()) {
+            actual.write_png(reference.with_extension("actual.png"))?;
+            return Err(SnapshotError::SizeMismatch {
+                expected: (width, height),
+                actual: (actual.width(), actual.height()),
+            });
+        }
+
+        let pixels = actual.to_rgba8();
+        let mut diff = Vec::with_capacity(pixels.len());
+        let mut mismatched = 0;
+        for (actual, expected) in pixels.chunks_exact(4).zip(expected.chunks_exact(4)) {
+            let matches = actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| a.abs_diff(*e) <= self.tolerance);
+
+            if matches {
+                let gray = (expected[0] as u16 + expected[1] as u16 + expected[2] as u16) / 3;
+                let faded = (gray as u8) / 4 + 191;
+                diff.extend_from_slice(&[faded, faded, faded, 255]);
+            } else {
+                mismatched += 1;
+                diff.extend_from_slice(&[255, 0, 0, 255]);
+            }
+        }
+
+        if mismatched == 0 {
+            return Ok(());
+        }
+
+        let diff_path = reference.with_extension("diff.png");
+        actual.write_png(reference.with_extension("actual.png"))?;
+        write_png(&diff_path, width, height, &diff)?;
+        Err(SnapshotError::Mismatch {
+            pixels: mismatched,
+            diff: diff_path,
+        })
+    }
+
+    /// Compare the element with the reference and panic on any error.
+    #[track_caller]
+    pub fn assert_matches<E: Element + ?Sized>(&self, element: &mut E, reference: impl AsRef<Path>) {
+        if let Err(e) = self.compare(element, reference.as_ref()) {
+            panic!("snapshot {} failed: {e}", reference.as_ref().display());
+        }
+    }
+}
+
+fn blessing() -> bool {
+    std::env::var_os(BLESS_VAR).is_some_and(|value| value != "0")
+}
+
+/// Decode the PNG into straight RGBA pixels, 8 bits per channel.
+fn read_png(path: &Path) -> Result<(u32, u32, Vec<u8>), png::DecodingError> {
+    let mut decoder = png::Decoder::new(std::fs::File::open(path)?);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+    buffer.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buffer,
+        png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+        png::ColorType::Indexed => unreachable!("palette is expanded by the decoder"),
+    };
+    Ok((info.width, info.height, rgba))
+}
+
+fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), png::EncodingError> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(rgba)
+}
@@ -0,0 +1,768 @@
+use std::io::Write;
+use crate::base::*;
+use crate::blend::BlendMode;
+use crate::image::Image;
+use crate::layout::{BoxConstraints, Context};
+use crate::math::*;
+use crate::path::{BoxShadow, Cap, Join, Path, Stroke, Verb};
+use crate::render::Canvas;
+use crate::text::Style;
+
+/// Number of sample rows per pixel used for anti-aliasing.
+/// Horizontal coverage is computed exactly.
+const SUBSAMPLES: usize = 4;
+
+/// Maximum distance in device pixels between a curve and its flattened polyline.
+const TOLERANCE: f32 = 0.25;
+
+/// In-memory RGBA image with premultiplied alpha.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Framebuffer {
+    /// Transparent framebuffer of given size in device pixels.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 4]; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Straight (not premultiplied) color of the pixel.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let [r, g, b, a] = self.pixels[(y * self.width + x) as usize];
+        if a <= 0.0 {
+            return [0; 4];
+        }
+
+        let channel = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        [channel(r / a), channel(g / a), channel(b / a), channel(a)]
+    }
+
+    /// Straight RGBA pixels, 8 bits per channel, stored row by row.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(self.pixels.len() * 4);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.extend_from_slice(&self.pixel(x, y));
+            }
+        }
+        result
+    }
+
+    pub fn encode_png(&self, writer: impl Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.to_rgba8())
+    }
+
+    pub fn write_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), png::EncodingError> {
+        let file = std::fs::File::create(path)?;
+        self.encode_png(std::io::BufWriter::new(file))
+    }
+
+    fn blend(&mut self, index: usize, color: [f32; 4], coverage: f32) {
+        let dst = &mut self.pixels[index];
+        let alpha = color[3] * coverage;
+        for i in 0..4 {
+            dst[i] = color[i] * coverage + dst[i] * (1.0 - alpha);
+        }
+    }
+}
+
+/// Backend rasterizing the drawing commands on the CPU into a [Framebuffer].
+pub struct SoftwareCanvas {
+    framebuffer: Framebuffer,
+    transforms: Vec<Affine>,
+    clips: Vec<Vec<f32>>,
+    stack: Vec<Entry>,
+}
+
+enum Entry {
+    Clip,
+    Transform,
+    /// Framebuffer below the layer with the opacity and the blend mode of the layer.
+    Layer(Framebuffer, PercentUnsigned, BlendMode),
+}
+
+impl SoftwareCanvas {
+    /// Canvas of given size in device pixels where every logical pixel spans
+    /// `scale` device pixels.
+    pub fn new(width: u32, height: u32, scale: Positive) -> Self {
+        let scale: f32 = scale.into();
+        Self {
+            framebuffer: Framebuffer::new(width, height),
+            transforms: vec![Affine::scale(scale, scale)],
+            clips: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.framebuffer
+    }
+
+    pub fn into_framebuffer(self) -> Framebuffer {
+        self.framebuffer
+    }
+
+    fn transform(&self) -> Affine {
+        *self.transforms.last().expect("base transform is never popped")
+    }
+
+    /// Approximate number of device pixels per logical pixel.
+    fn device_scale(&self) -> f32 {
+        let [a, b, c, d, _, _] = self.transform().coefficients();
+        (a * d - b * c).abs().sqrt().max(f32::EPSILON)
+    }
+
+    fn to_device(&self, polygons: Vec<Vec<(f32, f32)>>) -> Vec<Vec<(f32, f32)>> {
+        let transform = self.transform();
+        polygons
+            .into_iter()
+            .map(|polygon| polygon.into_iter().map(|(x, y)| transform.apply(x, y)).collect())
+            .collect()
+    }
+
+    /// Fill the device space polygons with the non-zero rule taking colors from
+    /// the source sampled at device pixel centers.
+    fn fill(&mut self, polygons: &[Vec<(f32, f32)>], source: impl Fn(f32, f32) -> [f32; 4]) {
+        let clip = self.clips.last();
+        let width = self.framebuffer.width as usize;
+        let framebuffer = &mut self.framebuffer;
+
+        rasterize(polygons, width, framebuffer.height as usize, |x, y, coverage| {
+            let index = y * width + x;
+            let coverage = coverage * clip.map(|mask| mask[index]).unwrap_or(1.0);
+            if coverage > 0.0 {
+                framebuffer.blend(index, source(x as f32 + 0.5, y as f32 + 0.5), coverage);
+            }
+        });
+    }
+}
+
+/// Premultiplied color of the shader at the device pixel.
+fn shade<'a>(shader: &'a Shader, transform: Affine) -> Box<dyn Fn(f32, f32) -> [f32; 4] + 'a> {
+    // Shapes drawn with degenerate transforms cover no pixels.
+    let Some(inverse) = transform.invert() else {
+        return Box::new(|_, _| [0.0; 4]);
+    };
+    let point = move |x: f32, y: f32| {
+        let (x, y) = inverse.apply(x, y);
+        Point::new(x, y)
+    };
+
+    match shader {
+        Shader::Solid(color) => {
+            let color = color.premultiplied();
+            Box::new(move |_, _| color)
+        }
+        Shader::Linear { start, end, gradient } => {
+            let axis = *end - *start;
+            let length = axis.x * axis.x + axis.y * axis.y;
+            Box::new(move |x, y| {
+                let offset = point(x, y) - *start;
+                let t = if length > 0.0 { (offset.x * axis.x + offset.y * axis.y) / length } else { 0.0 };
+                gradient.color_at(t).premultiplied()
+            })
+        }
+        Shader::Radial { center, radius, gradient } => {
+            let radius: f32 = (*radius).into();
+            Box::new(move |x, y| gradient.color_at((point(x, y) - *center).length() / radius).premultiplied())
+        }
+        Shader::Conic { center, angle, gradient } => Box::new(move |x, y| {
+            let angle: f32 = (*angle).into();
+            let offset = point(x, y) - *center;
+            let turn = (offset.y.atan2(offset.x) - angle) / std::f32::consts::TAU;
+            gradient.color_at(turn.rem_euclid(1.0)).premultiplied()
+        }),
+        // Patterns squashed to nothing cover no pixels.
+        Shader::Pattern { rect, .. } if rect.is_empty() => Box::new(|_, _| [0.0; 4]),
+        Shader::Pattern { image, rect } => {
+            let rect = *rect;
+            let (width, height) = (image.pixel_width() as f32, image.pixel_height() as f32);
+            Box::new(move |x, y| {
+                let Point { x, y } = point(x, y);
+                let u = (x - rect.x) / rect.width * width;
+                let v = (y - rect.y) / rect.height * height;
+                sample(image, u, v, true)
+            })
+        }
+    }
+}
+
+impl Canvas for SoftwareCanvas {
+    fn fill_rect(&mut self, rect: Rect, shader: &Shader) {
+        self.fill_path(&Path::rect(rect), shader);
+    }
+
+    fn fill_path(&mut self, path: &Path, shader: &Shader) {
+        let tolerance = TOLERANCE / self.device_scale();
+        let polygons = flatten(path, tolerance).into_iter().map(|(points, _)| points).collect();
+        let polygons = self.to_device(polygons);
+        let source = shade(shader, self.transform());
+        self.fill(&polygons, source);
+    }
+
+    fn stroke_path(&mut self, path: &Path, stroke: &Stroke, width: f32) {
+        let tolerance = TOLERANCE / self.device_scale();
+        let polygons = self.to_device(outline_stroke(path, stroke, width, tolerance));
+        let source = shade(&stroke.shader, self.transform());
+        self.fill(&polygons, source);
+    }
+
+    fn draw_shadow(&mut self, rect: Rect, radii: Radii, shadow: &BoxShadow) {
+        let tolerance = TOLERANCE / self.device_scale();
+        let polygons = |path: &Path| flatten(path, tolerance).into_iter().map(|(points, _)| points).collect();
+        let (shape, shape_radii) = shadow.shape(rect, radii);
+        let casting = self.to_device(polygons(&Path::rounded_rect(shape, shape_radii)));
+        let element = self.to_device(polygons(&Path::rounded_rect(rect, radii)));
+
+        let (width, height) = (self.framebuffer.width as usize, self.framebuffer.height as usize);
+        let coverage = |polygons: &[Vec<(f32, f32)>]| {
+            let mut mask = vec![0.0; width * height];
+            rasterize(polygons, width, height, |x, y, coverage| mask[y * width + x] = coverage);
+            mask
+        };
+
+        // Only the pixels the blur can reach around both shapes are processed.
+        let sigma = f32::from(shadow.blur) / 2.0 * self.device_scale();
+        let reach = (sigma * 3.0).ceil() + 1.0;
+        let points = || casting.iter().chain(&element).flatten();
+        let bound = |value: f32, limit: usize| value.clamp(0.0, limit as f32) as usize;
+        let region = (
+            bound(points().map(|p| p.0).fold(f32::INFINITY, f32::min) - reach, width),
+            bound(points().map(|p| p.1).fold(f32::INFINITY, f32::min) - reach, height),
+            bound((points().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max) + reach).ceil(), width),
+            bound((points().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max) + reach).ceil(), height),
+        );
+
+        let mut mask = coverage(&casting);
+        if shadow.inset {
+            mask.iter_mut().for_each(|value| *value = 1.0 - *value);
+        }
+        blur(&mut mask, width, region, sigma);
+
+        let inside = coverage(&element);
+        let color = shadow.color.premultiplied();
+        let (x0, y0, x1, y1) = region;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let index = y * width + x;
+                let visible = if shadow.inset { inside[index] } else { 1.0 - inside[index] };
+                let coverage = mask[index] * visible * self.clips.last().map(|clip| clip[index]).unwrap_or(1.0);
+                if coverage > 0.0 {
+                    self.framebuffer.blend(index, color, coverage);
+                }
+            }
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, style: &Style, rect: Rect) {
+        let Some(font) = style.resolve_font() else { return };
+        let baseline = rect.y + font.metrics(style.size).ascent;
+        self.fill_path(&font.outline(text, style, rect.x, baseline), &style.shader);
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: Rect) {
+        if rect.width <= 0.0 || rect.height <= 0.0 {
+            return;
+        }
+        let Some(inverse) = self.transform().invert() else { return };
+
+        let polygons = self.to_device(vec![corners(rect)]);
+        let (width, height) = (image.pixel_width(), image.pixel_height());
+        self.fill(&polygons, |x, y| {
+            let (x, y) = inverse.apply(x, y);
+            let u = (x - rect.x) / rect.width * width as f32;
+            let v = (y - rect.y) / rect.height * height as f32;
+            sample(image, u, v, false)
+        });
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let width = self.framebuffer.width as usize;
+        let height = self.framebuffer.height as usize;
+        let polygons = self.to_device(vec![corners(rect)]);
+
+        let mut mask = vec![0.0; width * height];
+        rasterize(&polygons, width, height, |x, y, coverage| {
+            mask[y * width + x] = coverage;
+        });
+        if let Some(parent) = self.clips.last() {
+            for (value, parent) in mask.iter_mut().zip(parent) {
+                *value *= parent;
+            }
+        }
+
+        self.clips.push(mask);
+        self.stack.push(Entry::Clip);
+    }
+
+    fn pop_clip(&mut self) {
+        match self.stack.pop() {
+            Some(Entry::Clip) => {
+                self.clips.pop();
+            }
+            _ => panic!("pop_clip does not match the last push"),
+        }
+    }
+
+    fn push_transform(&mut self, transform: Affine) {
+        let current = self.transform();
+        self.transforms.push(transform.then(&current));
+        self.stack.push(Entry::Transform);
+    }
+
+    fn pop_transform(&mut self) {
+        match self.stack.pop() {
+            Some(Entry::Transform) => {
+                self.transforms.pop();
+            }
+            _ => panic!("pop_transform does not match the last push"),
+        }
+    }
+
+    fn push_layer(&mut self, opacity: PercentUnsigned, mode: BlendMode) {
+        let layer = Framebuffer::new(self.framebuffer.width, self.framebuffer.height);
+        let below = std::mem::replace(&mut self.framebuffer, layer);
+        self.stack.push(Entry::Layer(below, opacity, mode));
+    }
+
+    fn pop_layer(&mut self) {
+        let Some(Entry::Layer(below, opacity, mode)) = self.stack.pop() else {
+            panic!("pop_layer does not match the last push");
+        };
+
+        let layer = std::mem::replace(&mut self.framebuffer, below);
+        let opacity: f32 = opacity.into();
+        let clip = self.clips.last();
+        for (index, (pixel, source)) in self.framebuffer.pixels.iter_mut().zip(&layer.pixels).enumerate() {
+            let coverage = clip.map(|mask| mask[index]).unwrap_or(1.0);
+            if coverage > 0.0 {
+                let result = mode.apply(source.map(|channel| channel * opacity), *pixel);
+                for i in 0..4 {
+                    pixel[i] += (result[i] - pixel[i]) * coverage;
+                }
+            }
+        }
+    }
+}
+
+/// Lay out the element to fill the given logical size and render it into a
+/// transparent framebuffer. Size of the framebuffer is the logical size
+/// multiplied by the scale factor.
+pub fn render<E: Element + ?Sized>(element: &mut E, size: Size, scale: Positive) -> Framebuffer {
+    let factor: f32 = scale.into();
+    let device_width = (size.widthf() * factor).ceil() as u32;
+    let device_height = (size.heightf() * factor).ceil() as u32;
+
+    let actual = element.layout(BoxConstraints::tight(size), &Context::default().with_scale(scale));
+    let mut canvas = SoftwareCanvas::new(device_width, device_height, scale);
+    element.paint(&mut canvas, Rect::sized(0.0, 0.0, actual));
+    canvas.into_framebuffer()
+}
+
+fn corners(rect: Rect) -> Vec<(f32, f32)> {
+    vec![
+        (rect.left(), rect.top()),
+        (rect.right(), rect.top()),
+        (rect.right(), rect.bottom()),
+        (rect.left(), rect.bottom()),
+    ]
+}
+
+/// Bilinear sample of the image at the pixel coordinates, which either repeat
+/// the image or extend its edge pixels past its bounds.
+fn sample(image: &Image, u: f32, v: f32, repeat: bool) -> [f32; 4] {
+    let width = image.pixel_width() as i64;
+    let height = image.pixel_height() as i64;
+    let pixel = |x: i64, y: i64| {
+        let (x, y) = if repeat {
+            (x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            (x.clamp(0, width - 1), y.clamp(0, height - 1))
+        };
+        let i = ((y * width + x) * 4) as usize;
+        let p = &image.pixels()[i..i + 4];
+        let a = p[3] as f32 / 255.0;
+        [p[0] as f32 / 255.0 * a, p[1] as f32 / 255.0 * a, p[2] as f32 / 255.0 * a, a]
+    };
+
+    if !u.is_finite() || !v.is_finite() {
+        return [0.0; 4];
+    }
+
+    // Bring far away coordinates next to the image so that the pixel indices
+    // cannot overflow, which does not change the sampled pixels.
+    let (u, v) = if repeat {
+        (u.rem_euclid(width as f32), v.rem_euclid(height as f32))
+    } else {
+        (u.clamp(-1.0, width as f32 + 1.0), v.clamp(-1.0, height as f32 + 1.0))
+    };
+    let (u, v) = (u - 0.5, v - 0.5);
+    let (x0, y0) = (u.floor(), v.floor());
+    let (fx, fy) = (u - x0, v - y0);
+    let (x0, y0) = (x0 as i64, y0 as i64);
+
+    let mut result = [0.0; 4];
+    for (x, y, weight) in [
+        (x0, y0, (1.0 - fx) * (1.0 - fy)),
+        (x0 + 1, y0, fx * (1.0 - fy)),
+        (x0, y0 + 1, (1.0 - fx) * fy),
+        (x0 + 1, y0 + 1, fx * fy),
+    ] {
+        let p = pixel(x, y);
+        for i in 0..4 {
+            result[i] += p[i] * weight;
+        }
+    }
+    result
+}
+
+/// Separable gaussian blur of the region of the row major mask given as left,
+/// top, right and bottom bounds. Values at the edges of the region extend past it.
+fn blur(mask: &mut [f32], width: usize, (x0, y0, x1, y1): (usize, usize, usize, usize), sigma: f32) {
+    if sigma <= 0.0 || x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    let radius = (sigma * 3.0).ceil() as isize;
+    let kernel: Vec<f32> = (-radius..=radius).map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp()).collect();
+    let total: f32 = kernel.iter().sum();
+    let convolve = |line: &[f32], i: usize| {
+        let last = line.len() as isize - 1;
+        let sum: f32 = kernel
+            .iter()
+            .enumerate()
+            .map(|(k, weight)| line[(i as isize + k as isize - radius).clamp(0, last) as usize] * weight)
+            .sum();
+        sum / total
+    };
+
+    let mut line = Vec::new();
+    for y in y0..y1 {
+        line.clear();
+        line.extend_from_slice(&mask[y * width + x0..y * width + x1]);
+        for x in x0..x1 {
+            mask[y * width + x] = convolve(&line, x - x0);
+        }
+    }
+    for x in x0..x1 {
+        line.clear();
+        line.extend((y0..y1).map(|y| mask[y * width + x]));
+        for y in y0..y1 {
+            mask[y * width + x] = convolve(&line, y - y0);
+        }
+    }
+}
+
+/// Scan convert the polygons with the non-zero fill rule. Calls `pixel` with
+/// the coverage of every pixel touched by the polygons.
+fn rasterize(polygons: &[Vec<(f32, f32)>], width: usize, height: usize, mut pixel: impl FnMut(usize, usize, f32)) {
+    let edges: Vec<_> = polygons
+        .iter()
+        .flat_map(|polygon| {
+            polygon
+                .iter()
+                .zip(polygon.iter().cycle().skip(1))
+                .filter(|(a, b)| a.1 != b.1)
+                .map(|(&a, &b)| (a, b))
+        })
+        .collect();
+    if edges.is_empty() || width == 0 {
+        return;
+    }
+
+    let min_y = edges.iter().map(|(a, b)| a.1.min(b.1)).fold(f32::INFINITY, f32::min);
+    let max_y = edges.iter().map(|(a, b)| a.1.max(b.1)).fold(f32::NEG_INFINITY, f32::max);
+    let first = min_y.floor().max(0.0) as usize;
+    let last = (max_y.ceil().max(0.0) as usize).min(height);
+
+    let mut row = vec![0.0f32; width];
+    let mut crossings = Vec::new();
+    let weight = 1.0 / SUBSAMPLES as f32;
+    for y in first..last {
+        row.iter_mut().for_each(|v| *v = 0.0);
+        let mut touched = (width, 0);
+
+        for sample in 0..SUBSAMPLES {
+            let sy = y as f32 + (sample as f32 + 0.5) * weight;
+            crossings.clear();
+            for &((x0, y0), (x1, y1)) in &edges {
+                let (top, bottom, winding) = if y0 < y1 { (y0, y1, 1) } else { (y1, y0, -1) };
+                if sy >= top && sy < bottom {
+                    crossings.push((x0 + (sy - y0) * (x1 - x0) / (y1 - y0), winding));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if winding != 0 {
+                    let (start, end) = span(&mut row, pair[0].0, pair[1].0, weight);
+                    touched = (touched.0.min(start), touched.1.max(end));
+                }
+            }
+        }
+
+        for (x, coverage) in row.iter().enumerate().take(touched.1).skip(touched.0) {
+            if *coverage > 0.0 {
+                pixel(x, y, coverage.min(1.0));
+            }
+        }
+    }
+}
+
+/// Add coverage of the horizontal span to the row. Returns the range of
+/// touched pixels.
+fn span(row: &mut [f32], start: f32, end: f32, weight: f32) -> (usize, usize) {
+    let width = row.len() as f32;
+    let (start, end) = (start.clamp(0.0, width), end.clamp(0.0, width));
+    if end <= start {
+        return (row.len(), 0);
+    }
+
+    let first = start.floor() as usize;
+    let last = end.floor() as usize;
+    if first == last {
+        row[first] += (end - start) * weight;
+        return (first, first + 1);
+    }
+
+    row[first] += (first as f32 + 1.0 - start) * weight;
+    for value in &mut row[first + 1..last] {
+        *value += weight;
+    }
+    if last < row.len() {
+        row[last] += (end - last as f32) * weight;
+    }
+    (first, (last + 1).min(row.len()))
+}
+
+/// Convert the path into polylines, returning each with a flag whether it
+/// was closed.
+fn flatten(path: &Path, tolerance: f32) -> Vec<(Vec<(f32, f32)>, bool)> {
+    let mut result = Vec::new();
+    let mut current: Vec<(f32, f32)> = Vec::new();
+    let mut finish = |current: &mut Vec<(f32, f32)>, closed: bool| {
+        if current.len() > 1 {
+            result.push((std::mem::take(current), closed));
+        }
+        current.clear();
+    };
+
+    for verb in path.verbs() {
+        let last = current.last().copied().unwrap_or((0.0, 0.0));
+        match *verb {
+            Verb::MoveTo(x, y) => {
+                finish(&mut current, false);
+                current.push((x, y));
+            }
+            Verb::LineTo(x, y) => {
+                if current.is_empty() {
+                    current.push(last);
+                }
+                current.push((x, y));
+            }
+            Verb::QuadTo(cx, cy, x, y) => {
+                if current.is_empty() {
+                    current.push(last);
+                }
+                let length = distance(last, (cx, cy)) + distance((cx, cy), (x, y));
+                let steps = segments(length, tolerance);
+                for i in 1..=steps {
+                    let t = i as f32 / steps as f32;
+                    let mt = 1.0 - t;
+                    current.push((
+                        mt * mt * last.0 + 2.0 * mt * t * cx + t * t * x,
+                        mt * mt * last.1 + 2.0 * mt * t * cy + t * t * y,
+                    ));
+                }
+            }
+            Verb::CubicTo(c1x, c1y, c2x, c2y, x, y) => {
+                if current.is_empty() {
+                    current.push(last);
+                }
+                let length = distance(last, (c1x, c1y)) + distance((c1x, c1y), (c2x, c2y)) + distance((c2x, c2y), (x, y));
+                let steps = segments(length, tolerance);
+                for i in 1..=steps {
+                    let t = i as f32 / steps as f32;
+                    let mt = 1.0 - t;
+                    let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                    current.push((
+                        a * last.0 + b * c1x + c * c2x + d * x,
+                        a * last.1 + b * c1y + c * c2y + d * y,
+                    ));
+                }
+            }
+            Verb::Close => {
+                let start = current.first().copied();
+                finish(&mut current, true);
+                if let Some(start) = start {
+                    current.push(start);
+                }
+            }
+        }
+    }
+    finish(&mut current, false);
+    result
+}
+
+fn segments(length: f32, tolerance: f32) -> usize {
+    ((length / tolerance).sqrt().ceil() as usize).clamp(1, 256)
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Polygons covering the stroke of the path. Each polygon is oriented
+/// clockwise so that overlapping parts are filled once by the non-zero rule.
+fn outline_stroke(path: &Path, stroke: &Stroke, width: f32, tolerance: f32) -> Vec<Vec<(f32, f32)>> {
+    let half = width / 2.0;
+    let mut polygons = Vec::new();
+
+    for (mut points, closed) in flatten(path, tolerance) {
+        points.dedup_by(|a, b| distance(*a, *b) <= f32::EPSILON);
+        if closed && points.len() > 1 && distance(points[0], points[points.len() - 1]) <= f32::EPSILON {
+            points.pop();
+        }
+        if points.len() < 2 {
+            continue;
+        }
+
+        let count = if closed { points.len() } else { points.len() - 1 };
+        let segment = |i: usize| (points[i], points[(i + 1) % points.len()]);
+        for i in 0..count {
+            let (a, b) = segment(i);
+            let (nx, ny) = normal(a, b);
+            polygons.push(vec![
+                (a.0 + nx * half, a.1 + ny * half),
+                (b.0 + nx * half, b.1 + ny * half),
+                (b.0 - nx * half, b.1 - ny * half),
+                (a.0 - nx * half, a.1 - ny * half),
+            ]);
+        }
+
+        let joins = if closed { 0..count } else { 1..count };
+        for i in joins {
+            let previous = segment((i + count - 1) % count);
+            let next = segment(i);
+            join(&mut polygons, previous, next, stroke.join, half, tolerance);
+        }
+
+        if !closed {
+            let (first, _) = segment(0);
+            let (_, second) = segment(0);
+            cap(&mut polygons, first, second, stroke.cap, half, tolerance);
+            let (before, last) = segment(count - 1);
+            cap(&mut polygons, last, before, stroke.cap, half, tolerance);
+        }
+    }
+
+    for polygon in &mut polygons {
+        if signed_area(polygon) < 0.0 {
+            polygon.reverse();
+        }
+    }
+    polygons
+}
+
+/// Unit normal of the segment.
+fn normal(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    let length = distance(a, b);
+    ((a.1 - b.1) / length, (b.0 - a.0) / length)
+}
+
+fn signed_area(polygon: &[(f32, f32)]) -> f32 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum()
+}
+
+fn circle(center: (f32, f32), radius: f32, tolerance: f32) -> Vec<(f32, f32)> {
+    let steps = (segments(radius * std::f32::consts::TAU, tolerance) * 2).max(8);
+    (0..steps)
+        .map(|i| {
+            let angle = i as f32 / steps as f32 * std::f32::consts::TAU;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+fn join(
+    polygons: &mut Vec<Vec<(f32, f32)>>,
+    previous: ((f32, f32), (f32, f32)),
+    next: ((f32, f32), (f32, f32)),
+    join: Join,
+    half: f32,
+    tolerance: f32,
+) {
+    let vertex = next.0;
+    let n1 = normal(previous.0, previous.1);
+    let n2 = normal(next.0, next.1);
+    let d1 = (previous.1 .0 - previous.0 .0, previous.1 .1 - previous.0 .1);
+    let d2 = (next.1 .0 - next.0 .0, next.1 .1 - next.0 .1);
+
+    // The outer side of the turn is opposite to the direction of turning.
+    let cross = d1.0 * d2.1 - d1.1 * d2.0;
+    let side = if cross > 0.0 { -1.0 } else { 1.0 };
+    let a = (vertex.0 + side * n1.0 * half, vertex.1 + side * n1.1 * half);
+    let b = (vertex.0 + side * n2.0 * half, vertex.1 + side * n2.1 * half);
+
+    match join {
+        Join::Round => polygons.push(circle(vertex, half, tolerance)),
+        Join::Bevel => polygons.push(vec![vertex, a, b]),
+        Join::Miter(limit) => {
+            let sum = (n1.0 + n2.0, n1.1 + n2.1);
+            let length = sum.0 * sum.0 + sum.1 * sum.1;
+            let limit: f32 = limit.into();
+
+            // Ratio of miter length to stroke width is 2 / |n1 + n2|.
+            if length > f32::EPSILON && 2.0 / length.sqrt() <= limit {
+                let k = side * 2.0 * half / length;
+                let tip = (vertex.0 + sum.0 * k, vertex.1 + sum.1 * k);
+                polygons.push(vec![vertex, a, tip, b]);
+            } else {
+                polygons.push(vec![vertex, a, b]);
+            }
+        }
+    }
+}
+
+/// Cap at the `end` point of the segment going from `from`.
+fn cap(polygons: &mut Vec<Vec<(f32, f32)>>, end: (f32, f32), from: (f32, f32), cap: Cap, half: f32, tolerance: f32) {
+    match cap {
+        Cap::Flat => {}
+        Cap::Round => polygons.push(circle(end, half, tolerance)),
+        Cap::Square => {
+            let length = distance(from, end);
+            let (dx, dy) = ((end.0 - from.0) / length * half, (end.1 - from.1) / length * half);
+            let (nx, ny) = normal(from, end);
+            polygons.push(vec![
+                (end.0 + nx * half, end.1 + ny * half),
+                (end.0 + nx * half + dx, end.1 + ny * half + dy),
+                (end.0 - nx * half + dx, end.1 - ny * half + dy),
+                (end.0 - nx * half, end.1 - ny * half),
+            ]);
+        }
+    }
+}
@@ -1,22 +1,89 @@
 use crate::base::*;
+use crate::layout::{Axis, BoxConstraints, Context};
+use crate::math::*;
+use crate::render::Canvas;
 
 pub struct Scroll<E: Element> {
     element: E,
+    axis: Axis,
+    offset: Unsigned,
+    content: Size,
 }
 
 impl<E: Element> Scroll<E> {
     pub fn new(element: E) -> Self {
-        Self { element }
+        Self::with_axis(element, Axis::Vertical)
+    }
+
+    pub fn with_axis(element: E, axis: Axis) -> Self {
+        Self {
+            element,
+            axis,
+            offset: Unsigned::zero(),
+            content: Size::zero(),
+        }
+    }
+
+    pub fn axis(&self) -> Axis {
+        self.axis
+    }
+
+    /// Distance the content is scrolled by from its start.
+    pub fn offset(&self) -> Unsigned {
+        self.offset
+    }
+
+    /// Scroll the content. The offset is limited to the scrollable extent on the
+    /// next layout.
+    pub fn scroll_to(&mut self, offset: Unsigned) {
+        self.offset = offset;
+    }
+
+    /// Rectangle of the content scrolled within the given one.
+    fn content_rect(&self, rect: Rect) -> Rect {
+        let offset: f32 = self.offset.into();
+        match self.axis {
+            Axis::Horizontal => Rect::sized(rect.x - offset, rect.y, self.content),
+            Axis::Vertical => Rect::sized(rect.x, rect.y - offset, self.content),
+        }
     }
 }
 
 impl<E: Element> Element for Scroll<E> {
-    fn width(&self) -> f32 {
-        self.element.width()
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        let child_constraints = match self.axis {
+            Axis::Horizontal => constraints.loosen().unbound_width(),
+            Axis::Vertical => constraints.loosen().unbound_height(),
+        };
+        self.content = self.element.layout(child_constraints, context);
+        let size = constraints.constrain(self.content.widthf(), self.content.heightf());
+
+        let extent = match self.axis {
+            Axis::Horizontal => self.content.widthf() - size.widthf(),
+            Axis::Vertical => self.content.heightf() - size.heightf(),
+        };
+        let extent = Unsigned::new(extent).unwrap_or(Unsigned::zero());
+        self.offset = self.offset.min(extent);
+
+        size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        let offset: f32 = self.offset.into();
+        match self.axis {
+            Axis::Horizontal => self.element.baseline(),
+            Axis::Vertical => self.element.baseline().map(|baseline| baseline - offset),
+        }
     }
 
-    fn height(&self) -> f32 {
-        self.element.height()
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        rect.contains_point(point) && self.element.hit_test(self.content_rect(rect), point)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        canvas.push_clip(rect);
+        self.element.paint(canvas, self.content_rect(rect));
+        canvas.pop_clip();
     }
 }
 
@@ -24,10 +91,8 @@ impl<E: Element> Layer<E> for Scroll<E> {
     fn inner(&self) -> &E {
         &self.element
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Direction {
-    Horizontal,
-    Vertical,
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
 }
@@ -1,13 +1,26 @@
 use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use thiserror::Error;
 use crate::base::*;
+use crate::layout::{BoxConstraints, Context};
 use crate::math::*;
+use crate::path::Path;
+use crate::render::Canvas;
+
+/// Font and family names of a style.
+type FontNames = (Cow<'static, str>, Cow<'static, str>);
 
 pub struct Text {
     value: Cow<'static, str>,
     style: Style,
+    inherit: bool,
+    baseline: Option<f32>,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     pub shader: Shader,
     pub background: Shader,
@@ -19,35 +32,361 @@ pub struct Style {
     pub strikeout: Option<Positive>,
     pub overline: Option<Positive>,
     pub italic: bool,
+    /// Multiplier of every glyph advance, `1.0` keeps the font spacing.
     pub letter_spacing: Positive,
+    /// Multiplier of the space advance, `1.0` keeps the font spacing.
     pub word_spacing: Positive,
 }
 
+impl Default for Style {
+    /// Black 16 pixel text of the `sans-serif` family.
+    fn default() -> Self {
+        Self {
+            shader: Shader::default(),
+            background: Color::transparent().into(),
+            font: Cow::Borrowed(""),
+            family: Cow::Borrowed("sans-serif"),
+            size: Positive::new(16.0).unwrap(),
+            weight: Positive::new(400.0).unwrap(),
+            underline: None,
+            strikeout: None,
+            overline: None,
+            italic: false,
+            letter_spacing: Positive::one(),
+            word_spacing: Positive::one(),
+        }
+    }
+}
+
+impl Style {
+    /// Find registered font by the font name, falling back to the family name.
+    pub fn resolve_font(&self) -> Option<Font> {
+        find_font(&self.font).or_else(|| find_font(&self.family))
+    }
+}
+
 impl Text {
     pub fn new(text: impl Into<Cow<'static, str>>, style: Style) -> Self {
         Self {
             value: text.into(),
             style,
+            inherit: false,
+            baseline: None,
         }
     }
 
+    /// Text in the style set by the closest [`TextStyle`](crate::layout::TextStyle)
+    /// ancestor, the default style without one.
+    pub fn inherited(text: impl Into<Cow<'static, str>>) -> Self {
+        let mut text = Self::new(text, Style::default());
+        text.inherit = true;
+        text
+    }
+
     pub fn value(&self) -> &str {
         &self.value
     }
 
+    /// Style of the text, the inherited one as of the last layout.
     pub fn style(&self) -> &Style {
         &self.style
     }
+
+    fn font(&self) -> Option<Font> {
+        // Text is laid out and painted every frame, so each missing pair of
+        // font and family names is reported once.
+        static WARNED: OnceLock<Mutex<HashSet<FontNames>>> = OnceLock::new();
+        let font = self.style.resolve_font();
+        if font.is_none() {
+            let key = (self.style.font.clone(), self.style.family.clone());
+            if WARNED.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner).insert(key) {
+                log::warn!("no font registered for `{}` nor `{}`", self.style.font, self.style.family);
+            }
+        }
+        font
+    }
 }
 
 impl Element for Text {
-    fn width(&self) -> f32 {
-        todo!()
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        if self.inherit && self.style != *context.text_style() {
+            self.style = context.text_style().clone();
+        }
+
+        let (width, height, baseline) = match self.font() {
+            Some(font) => {
+                let metrics = font.metrics(self.style.size);
+                (font.measure(&self.value, &self.style), metrics.line_height(), Some(metrics.ascent))
+            }
+            None => (0.0, self.style.size.into(), None),
+        };
+        self.baseline = baseline;
+
+        // Text is laid out on a single line and overflows if it does not fit.
+        constraints.constrain_min(width, height)
     }
 
-    fn height(&self) -> f32 {
-        todo!()
+    fn baseline(&self) -> Option<f32> {
+        self.baseline
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        let Some(font) = self.font() else { return };
+        let metrics = font.metrics(self.style.size);
+        let width = font.measure(&self.value, &self.style);
+        let baseline = rect.y + metrics.ascent;
+
+        canvas.fill_rect(Rect::new(rect.x, rect.y, width, metrics.line_height()), &self.style.background);
+        canvas.draw_text(&self.value, &self.style, rect);
+
+        let mut line = |offset: f32, thickness: Positive| {
+            let thickness: f32 = thickness.into();
+            let y = baseline - offset - thickness / 2.0;
+            canvas.fill_rect(Rect::new(rect.x, y, width, thickness), &self.style.shader);
+        };
+
+        if let Some(thickness) = self.style.underline {
+            line(metrics.underline_position, thickness);
+        }
+        if let Some(thickness) = self.style.strikeout {
+            line(metrics.strikeout_position, thickness);
+        }
+        if let Some(thickness) = self.style.overline {
+            line(metrics.ascent, thickness);
+        }
     }
 }
 
 impl Widget for Text {}
+
+static NEXT_FONT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Font face loaded from TrueType or OpenType data.
+///
+/// Clones share the data and are treated by the renderers as the same font.
+#[derive(Debug, Clone)]
+pub struct Font {
+    id: u64,
+    data: Arc<[u8]>,
+    index: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum FontError {
+    #[error("font data is malformed")]
+    Malformed,
+}
+
+/// Vertical font metrics in pixels for some font size.
+/// Positions are measured upwards from the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+    pub underline_position: f32,
+    pub underline_thickness: f32,
+    pub strikeout_position: f32,
+    pub strikeout_thickness: f32,
+}
+
+impl Metrics {
+    pub fn line_height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+}
+
+impl Font {
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Result<Self, FontError> {
+        Self::with_index(data, 0)
+    }
+
+    /// Load the face with given index from a font collection.
+    pub fn with_index(data: impl Into<Arc<[u8]>>, index: u32) -> Result<Self, FontError> {
+        let data = data.into();
+        ttf_parser::Face::parse(&data, index).map_err(|_| FontError::Malformed)?;
+
+        Ok(Self {
+            id: NEXT_FONT_ID.fetch_add(1, Ordering::Relaxed),
+            data,
+            index,
+        })
+    }
+
+    /// Identifier shared by all clones of the font.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub(crate) fn face(&self) -> ttf_parser::Face<'_> {
+        ttf_parser::Face::parse(&self.data, self.index).expect("font is validated on creation")
+    }
+
+    /// Pixels per font unit for given font size.
+    pub(crate) fn scale(&self, size: Positive) -> f32 {
+        let size: f32 = size.into();
+        size / self.face().units_per_em() as f32
+    }
+
+    pub fn metrics(&self, size: Positive) -> Metrics {
+        let face = self.face();
+        let scale = self.scale(size);
+        let underline = face.underline_metrics();
+        let strikeout = face.strikeout_metrics();
+
+        let ascent = face.ascender() as f32 * scale;
+        let descent = -face.descender() as f32 * scale;
+        Metrics {
+            ascent,
+            descent,
+            line_gap: face.line_gap() as f32 * scale,
+            underline_position: underline.map(|m| m.position as f32 * scale).unwrap_or(-descent / 2.0),
+            underline_thickness: underline.map(|m| m.thickness as f32 * scale).unwrap_or(1.0),
+            strikeout_position: strikeout.map(|m| m.position as f32 * scale).unwrap_or(ascent / 3.0),
+            strikeout_thickness: strikeout.map(|m| m.thickness as f32 * scale).unwrap_or(1.0),
+        }
+    }
+
+    /// Glyphs of the text shaped on a single line, in visual order, with the
+    /// advances scaled by the letter and word spacing.
+    pub fn shape(&self, text: &str, style: &Style) -> Vec<Glyph> {
+        let face = rustybuzz::Face::from_face(self.face());
+        let scale = self.scale(style.size);
+        let letter_spacing: f32 = style.letter_spacing.into();
+        let word_spacing: f32 = style.word_spacing.into();
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        let shaped = rustybuzz::shape(&face, &[], buffer);
+        shaped
+            .glyph_infos()
+            .iter()
+            .zip(shaped.glyph_positions())
+            .map(|(info, position)| {
+                let cluster = info.cluster as usize;
+                let mut advance = position.x_advance as f32 * scale * letter_spacing;
+                if text[cluster..].starts_with(' ') {
+                    advance *= word_spacing;
+                }
+                Glyph {
+                    id: info.glyph_id as u16,
+                    cluster,
+                    advance,
+                    // Font units grow upwards while canvas coordinates grow downwards.
+                    offset: Vector::new(position.x_offset as f32 * scale, -position.y_offset as f32 * scale),
+                }
+            })
+            .collect()
+    }
+
+    /// Width of the text laid out on a single line.
+    pub fn measure(&self, text: &str, style: &Style) -> f32 {
+        self.shape(text, style).iter().map(|glyph| glyph.advance).sum()
+    }
+
+    /// Glyph outlines of the text laid out on a single line starting at `x` on
+    /// the given baseline.
+    pub fn outline(&self, text: &str, style: &Style, x: f32, baseline: f32) -> Path {
+        let face = self.face();
+        let scale = self.scale(style.size);
+        let mut builder = OutlineBuilder {
+            path: Path::new(),
+            x,
+            baseline,
+            scale,
+        };
+
+        let mut pen = x;
+        for glyph in self.shape(text, style) {
+            builder.x = pen + glyph.offset.x;
+            builder.baseline = baseline + glyph.offset.y;
+            face.outline_glyph(ttf_parser::GlyphId(glyph.id), &mut builder);
+            pen += glyph.advance;
+        }
+        builder.path
+    }
+}
+
+/// Glyph of shaped text placed after the glyphs before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    pub id: u16,
+    /// Byte index in the text of the first character the glyph is shaped from.
+    pub cluster: usize,
+    /// Distance to the next glyph.
+    pub advance: f32,
+    /// Displacement of the glyph from its place, downwards for positive `y`.
+    pub offset: Vector,
+}
+
+struct OutlineBuilder {
+    path: Path,
+    x: f32,
+    baseline: f32,
+    scale: f32,
+}
+
+impl OutlineBuilder {
+    fn map(&self, x: f32, y: f32) -> (f32, f32) {
+        // Font units grow upwards while canvas coordinates grow downwards.
+        (self.x + x * self.scale, self.baseline - y * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.path.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.map(x, y);
+        self.path.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x, y) = self.map(x, y);
+        self.path.quad_to(x1, y1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (x1, y1) = self.map(x1, y1);
+        let (x2, y2) = self.map(x2, y2);
+        let (x, y) = self.map(x, y);
+        self.path.cubic_to(x1, y1, x2, y2, x, y);
+    }
+
+    fn close(&mut self) {
+        self.path.close();
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<Cow<'static, str>, Font>> {
+    static FONTS: OnceLock<RwLock<HashMap<Cow<'static, str>, Font>>> = OnceLock::new();
+    FONTS.get_or_init(Default::default)
+}
+
+/// Make the font available to [Style]s under the given font or family name.
+pub fn register_font(name: impl Into<Cow<'static, str>>, font: Font) {
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.into(), font);
+}
+
+pub fn find_font(name: &str) -> Option<Font> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
@@ -0,0 +1,98 @@
+use crate::base::*;
+use crate::layout::{BoxConstraints, Context};
+use crate::math::*;
+use crate::render::Canvas;
+
+/// Element painted with a transformation around an origin point.
+///
+/// The transformation does not affect layout, so the element keeps its place
+/// and size among its siblings and may be painted outside of its rectangle.
+pub struct Transform<E: Element> {
+    element: E,
+    transform: Affine,
+    x: PercentSigned,
+    y: PercentSigned,
+    size: Size,
+}
+
+impl<E: Element> Transform<E> {
+    /// Transformation around the center of the element.
+    pub const fn new(element: E, transform: Affine) -> Self {
+        Self::with_origin(element, transform, PercentSigned::zero(), PercentSigned::zero())
+    }
+
+    /// Transformation around the origin placed within the element the same way
+    /// as [`Align`](crate::layout::Align) places its element.
+    pub const fn with_origin(element: E, transform: Affine, x: PercentSigned, y: PercentSigned) -> Self {
+        Self { element, transform, x, y, size: Size::zero() }
+    }
+
+    pub const fn transform(&self) -> Affine {
+        self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.transform = transform;
+    }
+
+    pub const fn x(&self) -> PercentSigned {
+        self.x
+    }
+
+    pub const fn y(&self) -> PercentSigned {
+        self.y
+    }
+
+    /// Transformation of the element painted into the rectangle.
+    pub fn resolve(&self, rect: Rect) -> Affine {
+        let x: f32 = self.x.into();
+        let y: f32 = self.y.into();
+        let origin_x = rect.x + rect.width * (x + 1.0) / 2.0;
+        let origin_y = rect.y + rect.height * (y + 1.0) / 2.0;
+        Affine::translate(-origin_x, -origin_y)
+            .then(&self.transform)
+            .then(&Affine::translate(origin_x, origin_y))
+    }
+}
+
+impl<E: Element> Element for Transform<E> {
+    fn layout(&mut self, constraints: BoxConstraints, context: &Context) -> Size {
+        self.size = self.element.layout(constraints, context);
+        self.size
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.element.baseline()
+    }
+
+    fn hit_test(&self, rect: Rect, point: Point) -> bool {
+        // Degenerate transformations squash the element so nothing can hit it.
+        match self.resolve(rect).invert() {
+            Some(inverse) => self.element.hit_test(rect, inverse.transform_point(point)),
+            None => false,
+        }
+    }
+
+    /// Bounds of the transformed ink of the element.
+    fn ink_overflow(&self) -> Insets {
+        let rect = Rect::sized(0.0, 0.0, self.size);
+        let ink = self.resolve(rect).transform_rect(&(rect + self.element.ink_overflow()));
+        rect.overflow(&ink)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        canvas.push_transform(self.resolve(rect));
+        self.element.paint(canvas, rect);
+        canvas.pop_transform();
+    }
+}
+
+impl<E: Element> Layer<E> for Transform<E> {
+    fn inner(&self) -> &E {
+        &self.element
+    }
+
+    fn inner_mut(&mut self) -> &mut E {
+        &mut self.element
+    }
+}
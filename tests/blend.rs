@@ -0,0 +1,96 @@
+use haruhi::blend::BlendMode;
+
+const MODES: [BlendMode; 24] = [
+    BlendMode::Clear,
+    BlendMode::Source,
+    BlendMode::Destination,
+    BlendMode::SourceOver,
+    BlendMode::DestinationOver,
+    BlendMode::SourceIn,
+    BlendMode::DestinationIn,
+    BlendMode::SourceOut,
+    BlendMode::DestinationOut,
+    BlendMode::SourceAtop,
+    BlendMode::DestinationAtop,
+    BlendMode::Xor,
+    BlendMode::Plus,
+    BlendMode::Multiply,
+    BlendMode::Screen,
+    BlendMode::Overlay,
+    BlendMode::Darken,
+    BlendMode::Lighten,
+    BlendMode::ColorDodge,
+    BlendMode::ColorBurn,
+    BlendMode::HardLight,
+    BlendMode::SoftLight,
+    BlendMode::Difference,
+    BlendMode::Exclusion,
+];
+
+fn assert_close(mode: BlendMode, actual: [f32; 4], expected: [f32; 4]) {
+    let close = actual.iter().zip(expected).all(|(a, e)| (a - e).abs() < 1e-4);
+    assert!(close, "{mode:?}: {actual:?} != {expected:?}");
+}
+
+#[test]
+fn porter_duff() {
+    // Half transparent red onto opaque blue.
+    let source = [0.5, 0.0, 0.0, 0.5];
+    let destination = [0.0, 0.0, 1.0, 1.0];
+    let cases = [
+        (BlendMode::Clear, [0.0, 0.0, 0.0, 0.0]),
+        (BlendMode::Source, source),
+        (BlendMode::Destination, destination),
+        (BlendMode::SourceOver, [0.5, 0.0, 0.5, 1.0]),
+        (BlendMode::DestinationOver, destination),
+        (BlendMode::SourceIn, source),
+        (BlendMode::DestinationIn, [0.0, 0.0, 0.5, 0.5]),
+        (BlendMode::SourceOut, [0.0, 0.0, 0.0, 0.0]),
+        (BlendMode::DestinationOut, [0.0, 0.0, 0.5, 0.5]),
+        (BlendMode::SourceAtop, [0.5, 0.0, 0.5, 1.0]),
+        (BlendMode::DestinationAtop, [0.0, 0.0, 0.5, 0.5]),
+        (BlendMode::Xor, [0.0, 0.0, 0.5, 0.5]),
+        (BlendMode::Plus, [0.5, 0.0, 1.0, 1.0]),
+    ];
+    for (mode, expected) in cases {
+        assert_close(mode, mode.apply(source, destination), expected);
+    }
+}
+
+#[test]
+fn separable() {
+    let source = [0.2, 0.6, 1.0, 1.0];
+    let destination = [0.5, 0.5, 0.5, 1.0];
+    let cases = [
+        (BlendMode::Multiply, [0.1, 0.3, 0.5, 1.0]),
+        (BlendMode::Screen, [0.6, 0.8, 1.0, 1.0]),
+        (BlendMode::Overlay, [0.2, 0.6, 1.0, 1.0]),
+        (BlendMode::Darken, [0.2, 0.5, 0.5, 1.0]),
+        (BlendMode::Lighten, [0.5, 0.6, 1.0, 1.0]),
+        (BlendMode::ColorDodge, [0.625, 1.0, 1.0, 1.0]),
+        (BlendMode::ColorBurn, [0.0, 1.0 / 6.0, 0.5, 1.0]),
+        (BlendMode::HardLight, [0.2, 0.6, 1.0, 1.0]),
+        (BlendMode::SoftLight, [0.35, 0.5 + 0.2 * (0.5f32.sqrt() - 0.5), 0.5f32.sqrt(), 1.0]),
+        (BlendMode::Difference, [0.3, 0.1, 0.5, 1.0]),
+        (BlendMode::Exclusion, [0.5, 0.5, 0.5, 1.0]),
+    ];
+    for (mode, expected) in cases {
+        assert_close(mode, mode.apply(source, destination), expected);
+    }
+
+    // Only the covered part of the destination is mixed, premultiplied colors
+    // are blended as straight ones.
+    let source = [0.5, 0.0, 0.0, 0.5];
+    let destination = [0.0, 0.0, 1.0, 1.0];
+    assert_close(BlendMode::Multiply, BlendMode::Multiply.apply(source, destination), [0.0, 0.0, 0.5, 1.0]);
+    assert_close(BlendMode::Screen, BlendMode::Screen.apply(source, destination), [0.5, 0.0, 1.0, 1.0]);
+}
+
+#[test]
+fn preserves_destination() {
+    let destination = [0.1, 0.2, 0.3, 0.4];
+    for mode in MODES {
+        let preserved = mode.apply([0.0; 4], destination) == destination;
+        assert_eq!(mode.preserves_destination(), preserved, "{mode:?}");
+    }
+}
@@ -0,0 +1,140 @@
+use haruhi::base::Color;
+use haruhi::color::*;
+use haruhi::math::*;
+use proptest::prelude::*;
+
+fn rgba8() -> impl Strategy<Value = [u8; 4]> {
+    any::<[u8; 4]>()
+}
+
+fn percent(value: f32) -> PercentUnsigned {
+    PercentUnsigned::new(value).unwrap()
+}
+
+fn parse(value: &str) -> [u8; 4] {
+    value.parse::<Color>().unwrap().to_rgba8()
+}
+
+proptest! {
+    #[test]
+    fn hsl_round_trips(rgba in rgba8()) {
+        let color = Color::from_rgba8(rgba);
+        prop_assert_eq!(Color::from(Hsl::from(color)).to_rgba8(), rgba);
+    }
+
+    #[test]
+    fn hsv_round_trips(rgba in rgba8()) {
+        let color = Color::from_rgba8(rgba);
+        prop_assert_eq!(Color::from(Hsv::from(color)).to_rgba8(), rgba);
+    }
+
+    #[test]
+    fn oklch_round_trips(rgba in rgba8()) {
+        let color = Color::from_rgba8(rgba);
+        prop_assert_eq!(Color::from(Oklch::from(color)).to_rgba8(), rgba);
+        prop_assert_eq!(Color::from(LinearRgb::from(color)).to_rgba8(), rgba);
+    }
+
+    #[test]
+    fn display_round_trips(rgba in rgba8()) {
+        let color = Color::from_rgba8(rgba);
+        prop_assert_eq!(color.to_string().parse::<Color>().unwrap(), color);
+    }
+
+    #[test]
+    fn contrast_is_symmetric(a in rgba8(), b in rgba8()) {
+        let (a, b) = (Color::from_rgba8(a), Color::from_rgba8(b));
+        let ratio = a.contrast_ratio(&b);
+        prop_assert_eq!(ratio, b.contrast_ratio(&a));
+        prop_assert!((1.0..=21.0 + 1e-4).contains(&ratio));
+    }
+}
+
+#[test]
+fn parse_hex() {
+    assert_eq!(parse("#f80"), [255, 136, 0, 255]);
+    assert_eq!(parse("#f808"), [255, 136, 0, 136]);
+    assert_eq!(parse("#FF8000"), [255, 128, 0, 255]);
+    assert_eq!(parse(" #ff800080 "), [255, 128, 0, 128]);
+    assert_eq!("#ff800".parse::<Color>(), Err(ColorError::InvalidHex));
+    assert_eq!("#gg8000".parse::<Color>(), Err(ColorError::InvalidHex));
+    assert_eq!("#".parse::<Color>(), Err(ColorError::InvalidHex));
+}
+
+#[test]
+fn parse_functions() {
+    assert_eq!(parse("rgb(255, 128, 0)"), [255, 128, 0, 255]);
+    assert_eq!(parse("rgba(255, 128, 0, 0.5)"), [255, 128, 0, 128]);
+    assert_eq!(parse("rgb(100% 50% 0% / 50%)"), [255, 128, 0, 128]);
+    assert_eq!(parse("RGB(300, -20, 0)"), [255, 0, 0, 255]);
+    assert_eq!(parse("hsl(120, 100%, 25%)"), [0, 128, 0, 255]);
+    assert_eq!(parse("hsla(0.5turn 100% 50% / 0.25)"), [0, 255, 255, 64]);
+    assert_eq!(parse("hsl(3.14159rad, 100%, 50%)"), [0, 255, 255, 255]);
+    assert_eq!(parse("hsl(-120deg 100 50)"), [0, 0, 255, 255]);
+    assert_eq!(parse("hsl(200grad, 100%, 50%)"), [0, 255, 255, 255]);
+    assert_eq!(parse("oklch(62.8% 0.2577 29.23)"), [255, 0, 0, 255]);
+    assert_eq!(parse("oklch(1 0 0)"), [255, 255, 255, 255]);
+    assert_eq!("rgb(1, 2)".parse::<Color>(), Err(ColorError::InvalidFunction));
+    assert_eq!("rgb(1, 2, 3".parse::<Color>(), Err(ColorError::InvalidFunction));
+    assert_eq!("lab(50 0 0)".parse::<Color>(), Err(ColorError::InvalidFunction));
+    assert_eq!("rgb(1, red, 3)".parse::<Color>(), Err(ColorError::InvalidComponent));
+    assert_eq!("hsl(nan, 0%, 0%)".parse::<Color>(), Err(ColorError::InvalidComponent));
+}
+
+#[test]
+fn parse_names() {
+    assert_eq!(parse("rebeccapurple"), [102, 51, 153, 255]);
+    assert_eq!(parse("CornflowerBlue"), [100, 149, 237, 255]);
+    assert_eq!("transparent".parse::<Color>(), Ok(Color::transparent()));
+    assert_eq!("white".parse::<Color>(), Ok(Color::white()));
+    assert_eq!("blurple".parse::<Color>(), Err(ColorError::UnknownName));
+    assert_eq!("".parse::<Color>(), Err(ColorError::UnknownName));
+}
+
+#[test]
+fn display() {
+    assert_eq!(Color::from_rgba8([255, 128, 0, 255]).to_string(), "#ff8000");
+    assert_eq!(Color::from_rgba8([255, 128, 0, 128]).to_string(), "#ff800080");
+    assert_eq!(Color::transparent().to_string(), "#00000000");
+}
+
+#[test]
+fn conversions() {
+    let orange = Color::from_rgba8([255, 128, 0, 255]);
+    let hsl = Hsl::from(orange);
+    assert!((hsl.hue - 30.1).abs() < 0.1);
+    assert_eq!(hsl.saturation, PercentUnsigned::one());
+    let hsv = Hsv::from(orange);
+    assert_eq!(hsv.value, PercentUnsigned::one());
+
+    let lab = Oklab::from(Color::white());
+    assert!((lab.lightness - 1.0).abs() < 1e-4 && lab.a.abs() < 1e-4 && lab.b.abs() < 1e-4);
+    let lch = Oklch::from(Color::from_rgba8([0, 0, 255, 255]));
+    assert!((lch.lightness - 0.452).abs() < 1e-3 && (lch.chroma - 0.313).abs() < 1e-3 && (lch.hue - 264.05).abs() < 0.1);
+
+    let linear = LinearRgb::from(Color::from_rgba8([128, 128, 128, 255]));
+    assert!((linear.red - 0.2159).abs() < 1e-4);
+
+    // Out of gamut colors are clamped.
+    let vivid = Color::from(Oklch { lightness: 0.7, chroma: 0.4, hue: 150.0, alpha: PercentUnsigned::one() });
+    assert_eq!(vivid.to_rgba8()[0], 0);
+}
+
+#[test]
+fn helpers() {
+    let gray = Color::from_rgba8([128, 128, 128, 255]);
+    assert_eq!(gray.lighten(percent(0.2)).to_rgba8(), [179, 179, 179, 255]);
+    assert_eq!(gray.darken(percent(0.2)).to_rgba8(), [77, 77, 77, 255]);
+    assert_eq!(gray.lighten(PercentUnsigned::one()), Color::white());
+    assert_eq!(gray.darken(PercentUnsigned::one()), Color::black());
+    assert_eq!(gray.with_alpha(percent(0.5)).alpha, percent(0.5));
+
+    let red = Color::from_rgba8([255, 0, 0, 255]);
+    assert_eq!(Color::black().mix(Color::white(), percent(0.5)).to_rgba8(), [128, 128, 128, 255]);
+    assert_eq!(red.mix(Color::transparent(), percent(0.5)).to_rgba8(), [255, 0, 0, 128]);
+    assert_eq!(red.mix(Color::white(), PercentUnsigned::zero()), red);
+
+    assert!((Color::black().contrast_ratio(&Color::white()) - 21.0).abs() < 1e-4);
+    assert_eq!(red.contrast_ratio(&red), 1.0);
+    assert!((Color::from_rgba8([118, 118, 118, 255]).contrast_ratio(&Color::white()) - 4.54).abs() < 0.01);
+}
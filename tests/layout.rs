@@ -0,0 +1,439 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use haruhi::base::*;
+use haruhi::dynamic::{Alive, Update};
+use haruhi::layout::*;
+use haruhi::math::*;
+use haruhi::path::*;
+use haruhi::render::Canvas;
+use haruhi::render::software::render;
+use haruhi::scroll::Scroll;
+use haruhi::text::{Style, Text};
+use haruhi::transform::Transform;
+
+fn unsigned(value: f32) -> Unsigned {
+    Unsigned::new(value).unwrap()
+}
+
+fn size(width: f32, height: f32) -> Size {
+    Size::new(unsigned(width), unsigned(height))
+}
+
+/// What happened to a [`Leaf`] during the last layout and paint.
+#[derive(Default)]
+struct Record {
+    rect: Cell<Rect>,
+    layouts: Cell<usize>,
+}
+
+/// Element of a natural size recording the rectangle it is painted into.
+struct Leaf {
+    width: f32,
+    height: f32,
+    baseline: Option<f32>,
+    record: Rc<Record>,
+}
+
+fn leaf(width: f32, height: f32) -> (Leaf, Rc<Record>) {
+    let record = Rc::new(Record::default());
+    (Leaf { width, height, baseline: None, record: record.clone() }, record)
+}
+
+/// Leaf reporting the baseline at the given distance from its top.
+fn text(width: f32, height: f32, baseline: f32) -> (Leaf, Rc<Record>) {
+    let (mut leaf, record) = leaf(width, height);
+    leaf.baseline = Some(baseline);
+    (leaf, record)
+}
+
+impl Element for Leaf {
+    fn layout(&mut self, constraints: BoxConstraints, _context: &Context) -> Size {
+        self.record.layouts.set(self.record.layouts.get() + 1);
+        constraints.constrain(self.width, self.height)
+    }
+
+    fn baseline(&self) -> Option<f32> {
+        self.baseline
+    }
+
+    fn paint(&self, _canvas: &mut dyn Canvas, rect: Rect) {
+        self.record.rect.set(rect);
+    }
+}
+
+impl Alive for Leaf {}
+
+/// Change of the natural width of a [`Leaf`].
+struct Widen(f32);
+
+impl Update for Widen {
+    type Element = Leaf;
+
+    fn apply(self, leaf: &mut Leaf) {
+        leaf.width = self.0;
+    }
+}
+
+/// Lay out the element to fill the size and paint it.
+fn place<E: Element>(element: &mut E, width: f32, height: f32) {
+    render(element, size(width, height), Positive::one());
+}
+
+fn lefts<const N: usize>(records: &[Rc<Record>; N]) -> [f32; N] {
+    std::array::from_fn(|i| records[i].rect.get().x)
+}
+
+#[test]
+fn flex() {
+    let (rigid, a) = leaf(10.0, 10.0);
+    let (one, b) = leaf(0.0, 10.0);
+    let (three, c) = leaf(0.0, 10.0);
+    let flex = |value| Flex::new(value).unwrap();
+    let mut row = Row::new((rigid, Flexible::new(one, flex(1.0)), Flexible::new(three, flex(3.0))));
+    place(&mut row, 90.0, 10.0);
+    assert_eq!(a.rect.get(), Rect::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(b.rect.get(), Rect::new(10.0, 0.0, 20.0, 10.0));
+    assert_eq!(c.rect.get(), Rect::new(30.0, 0.0, 60.0, 10.0));
+
+    // Flexible elements along an unbounded axis keep their natural size.
+    let (natural, d) = leaf(7.0, 10.0);
+    let mut column = Scroll::new(Column::new(vec![Flexible::new(natural, flex(1.0))]));
+    place(&mut column, 10.0, 20.0);
+    assert_eq!(d.rect.get().height, 10.0);
+}
+
+#[test]
+fn justify() {
+    let cases = [
+        (Justify::Start, [0.0, 10.0, 20.0]),
+        (Justify::End, [70.0, 80.0, 90.0]),
+        (Justify::Center, [35.0, 45.0, 55.0]),
+        (Justify::SpaceBetween, [0.0, 45.0, 90.0]),
+        (Justify::SpaceAround, [70.0 / 6.0, 10.0 + 70.0 / 2.0, 20.0 + 70.0 * 5.0 / 6.0]),
+        (Justify::SpaceEvenly, [17.5, 45.0, 72.5]),
+    ];
+    for (justify, expected) in cases {
+        let (a, ra) = leaf(10.0, 10.0);
+        let (b, rb) = leaf(10.0, 10.0);
+        let (c, rc) = leaf(10.0, 10.0);
+        place(&mut Row::new((a, b, c)).justify(justify), 100.0, 10.0);
+        let actual = lefts(&[ra, rb, rc]);
+        for (actual, expected) in actual.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-4, "{justify:?}: {actual} != {expected}");
+        }
+    }
+}
+
+#[test]
+fn cross_align() {
+    let cases = [
+        (CrossAlign::Start, [(0.0, 10.0), (0.0, 20.0)]),
+        (CrossAlign::End, [(20.0, 10.0), (10.0, 20.0)]),
+        (CrossAlign::Center, [(10.0, 10.0), (5.0, 20.0)]),
+        (CrossAlign::Stretch, [(0.0, 30.0), (0.0, 30.0)]),
+    ];
+    for (cross_align, expected) in cases {
+        let (a, ra) = leaf(10.0, 10.0);
+        let (b, rb) = leaf(10.0, 20.0);
+        place(&mut Row::new((a, b)).cross_align(cross_align), 40.0, 30.0);
+        let actual = [ra, rb].map(|record| (record.rect.get().y, record.rect.get().height));
+        assert_eq!(actual, expected, "{cross_align:?}");
+    }
+}
+
+#[test]
+fn mixed_children() {
+    let (a, ra) = leaf(10.0, 10.0);
+    let (b, rb) = leaf(20.0, 10.0);
+    let mut row = Row::new((a, Space::all(b, unsigned(5.0)), Blank));
+    place(&mut row, 60.0, 20.0);
+    assert_eq!(ra.rect.get(), Rect::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(rb.rect.get(), Rect::new(15.0, 5.0, 20.0, 10.0));
+    assert_eq!(row.children().len(), 3);
+
+    let (c, rc) = leaf(10.0, 10.0);
+    let (d, rd) = leaf(10.0, 10.0);
+    let mut column = Column::new(vec![Box::new(c) as AnyElement, Box::new(Space::vertical(d, unsigned(4.0), unsigned(0.0)))]);
+    place(&mut column, 10.0, 40.0);
+    assert_eq!(rc.rect.get(), Rect::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(rd.rect.get(), Rect::new(0.0, 14.0, 10.0, 10.0));
+
+    let (e, re) = leaf(10.0, 10.0);
+    column.children_mut().insert(0, Box::new(e));
+    place(&mut column, 10.0, 40.0);
+    assert_eq!(re.rect.get(), Rect::new(0.0, 0.0, 10.0, 10.0));
+    assert_eq!(rd.rect.get(), Rect::new(0.0, 24.0, 10.0, 10.0));
+}
+
+#[test]
+fn row_and_column_axes() {
+    let (a, ra) = leaf(10.0, 20.0);
+    let (b, rb) = leaf(30.0, 5.0);
+    let mut row = Align::top_left(Row::new((a, b)));
+    place(&mut row, 100.0, 100.0);
+    assert_eq!(ra.rect.get(), Rect::new(0.0, 0.0, 10.0, 20.0));
+    assert_eq!(rb.rect.get(), Rect::new(10.0, 0.0, 30.0, 5.0));
+    assert_eq!(row.inner_mut().layout(BoxConstraints::unbounded(), &Context::default()), size(40.0, 20.0));
+
+    let (a, ra) = leaf(10.0, 20.0);
+    let (b, rb) = leaf(30.0, 5.0);
+    let mut column = Align::top_left(Column::new((a, b)));
+    place(&mut column, 100.0, 100.0);
+    assert_eq!(ra.rect.get(), Rect::new(0.0, 0.0, 10.0, 20.0));
+    assert_eq!(rb.rect.get(), Rect::new(0.0, 20.0, 30.0, 5.0));
+    assert_eq!(column.inner_mut().layout(BoxConstraints::unbounded(), &Context::default()), size(30.0, 25.0));
+}
+
+#[test]
+fn grid_overflowing_tracks() {
+    let (a, ra) = leaf(10.0, 10.0);
+    let (b, rb) = leaf(10.0, 10.0);
+    let (c, rc) = leaf(10.0, 10.0);
+    let huge = Track::Fixed(unsigned(f32::MAX));
+    let mut grid = Grid::new((a, b, c), vec![huge, huge], vec![huge, huge]).cell(2, haruhi::layout::Cell::new(1, 0).span(1, 2));
+    place(&mut grid, 100.0, 100.0);
+    assert_eq!(ra.rect.get(), Rect::new(0.0, 0.0, f32::MAX, f32::MAX));
+    assert_eq!(rb.rect.get().x, f32::MAX);
+    assert_eq!(rc.rect.get(), Rect::new(0.0, f32::MAX, f32::MAX, f32::MAX));
+}
+
+#[test]
+fn container_caching() {
+    let (a, ra) = leaf(10.0, 10.0);
+    let (b, rb) = leaf(10.0, 10.0);
+    let mut row = Row::new((a, b));
+    place(&mut row, 40.0, 10.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (1, 1));
+
+    // The same constraints hit, other ones miss.
+    place(&mut row, 40.0, 10.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (1, 1));
+    place(&mut row, 40.0, 20.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (2, 2));
+    place(&mut row, 40.0, 10.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (3, 3));
+    assert_eq!(rb.rect.get(), Rect::new(10.0, 0.0, 10.0, 10.0));
+
+    row.children_mut();
+    place(&mut row, 40.0, 10.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (4, 4));
+
+    // Changing one child keeps the sizes of its siblings, also through the
+    // ancestors of the changed child.
+    row.children_mut_at(1).1.width = 20.0;
+    place(&mut row, 40.0, 10.0);
+    assert_eq!((ra.layouts.get(), rb.layouts.get()), (4, 5));
+    assert_eq!(rb.rect.get(), Rect::new(10.0, 0.0, 20.0, 10.0));
+
+    let (c, rc) = leaf(10.0, 10.0);
+    let (d, rd) = leaf(10.0, 10.0);
+    let (e, re) = leaf(10.0, 10.0);
+    let mut column = Column::new((Row::new((c, d)), e));
+    place(&mut column, 40.0, 20.0);
+    column.children_mut_at(0).0.children_mut_at(0).0.height = 5.0;
+    place(&mut column, 40.0, 20.0);
+    assert_eq!([rc.layouts.get(), rd.layouts.get(), re.layouts.get()], [2, 1, 1]);
+
+    // Each pass of the grid lays out its elements with other constraints, the
+    // next layout takes all of them from the cache.
+    let (c, rc) = leaf(10.0, 10.0);
+    let flex = Track::Flex(Flex::default());
+    let mut grid = Grid::new(vec![c], vec![flex], vec![flex]);
+    place(&mut grid, 40.0, 40.0);
+    assert_eq!(rc.layouts.get(), 3);
+    place(&mut grid, 40.0, 40.0);
+    assert_eq!(rc.layouts.get(), 3);
+
+    // The unbounded pass still hits for a grid of another size.
+    place(&mut grid, 30.0, 30.0);
+    assert_eq!(rc.layouts.get(), 5);
+
+    // Going back to a size measured before lays the element out again so that
+    // it is left with the layout it is painted with.
+    place(&mut grid, 40.0, 40.0);
+    assert_eq!(rc.layouts.get(), 6);
+    assert_eq!(rc.rect.get(), Rect::new(0.0, 0.0, 40.0, 40.0));
+}
+
+#[test]
+fn cached() {
+    let (a, ra) = leaf(10.0, 10.0);
+    let mut cached = Cached::new(a);
+    let context = Context::default();
+    let loose = BoxConstraints::loose(size(40.0, 40.0));
+    assert_eq!(cached.layout(loose, &context), size(10.0, 10.0));
+    assert_eq!(cached.layout(loose, &context), size(10.0, 10.0));
+    assert_eq!(ra.layouts.get(), 1);
+
+    cached.layout(BoxConstraints::unbounded(), &context);
+    assert_eq!(ra.layouts.get(), 2);
+    cached.layout(BoxConstraints::unbounded(), &context.clone().with_scale(Positive::new(2.0).unwrap()));
+    assert_eq!(ra.layouts.get(), 3);
+
+    cached.inner_mut().height = 20.0;
+    assert_eq!(cached.layout(loose, &context), size(10.0, 20.0));
+    assert_eq!(ra.layouts.get(), 4);
+
+    cached.update(Widen(30.0));
+    assert_eq!(cached.layout(loose, &context), size(30.0, 20.0));
+    assert_eq!(ra.layouts.get(), 5);
+
+    cached.refresh();
+    cached.layout(loose, &context);
+    assert_eq!(ra.layouts.get(), 6);
+    cached.inner();
+    cached.layout(loose, &context);
+    assert_eq!(ra.layouts.get(), 6);
+}
+
+#[test]
+fn baseline() {
+    let context = Context::default();
+    let stroke = Stroke {
+        width: Length::Px(unsigned(2.0)),
+        shader: Shader::default(),
+        cap: Cap::Flat,
+        join: Join::Miter(unsigned(4.0)),
+    };
+
+    let mut space = Space::all(text(10.0, 10.0, 5.0).0, unsigned(3.0));
+    space.layout(BoxConstraints::unbounded(), &context);
+    assert_eq!(space.baseline(), Some(8.0));
+
+    let mut border = Border::new(text(10.0, 10.0, 5.0).0, stroke.clone());
+    border.layout(BoxConstraints::unbounded(), &context);
+    assert_eq!(border.baseline(), Some(7.0));
+
+    let mut align = Align::center(text(10.0, 10.0, 5.0).0);
+    align.layout(BoxConstraints::tight(size(40.0, 40.0)), &context);
+    assert_eq!(align.baseline(), Some(20.0));
+
+    let mut blank = Space::all(Blank, unsigned(3.0));
+    blank.layout(BoxConstraints::unbounded(), &context);
+    assert_eq!(blank.baseline(), None);
+
+    // Elements sit on the lowest baseline, those without one on their bottom
+    // edge, the row reports the baseline of the first element.
+    let (a, ra) = text(10.0, 20.0, 15.0);
+    let (b, rb) = text(10.0, 10.0, 5.0);
+    let (c, rc) = text(10.0, 10.0, 5.0);
+    let (d, rd) = leaf(10.0, 8.0);
+    let mut row = Align::top_left(Row::new((a, Space::all(b, unsigned(3.0)), Border::new(c, stroke), d)).cross_align(CrossAlign::Baseline));
+    place(&mut row, 100.0, 100.0);
+    assert_eq!(ra.rect.get(), Rect::new(0.0, 0.0, 10.0, 20.0));
+    assert_eq!(rb.rect.get(), Rect::new(13.0, 10.0, 10.0, 10.0));
+    assert_eq!(rc.rect.get(), Rect::new(28.0, 10.0, 10.0, 10.0));
+    assert_eq!(rd.rect.get(), Rect::new(40.0, 7.0, 10.0, 8.0));
+    assert_eq!(row.inner().baseline(), Some(15.0));
+    assert_eq!(row.baseline(), Some(15.0));
+
+    // A column reports the baseline of its first element having one.
+    let (e, _) = leaf(10.0, 6.0);
+    let (f, _) = text(10.0, 10.0, 5.0);
+    let mut column = Column::new((e, f));
+    column.layout(BoxConstraints::unbounded(), &context);
+    assert_eq!(column.baseline(), Some(11.0));
+}
+
+#[test]
+fn hit_test() {
+    let rotated = || Transform::new(leaf(20.0, 20.0).0, Affine::rotate(std::f32::consts::FRAC_PI_4));
+    let hits = |element: &dyn Element, rect: Rect, points: &[(f32, f32)]| -> Vec<bool> {
+        points.iter().map(|&(x, y)| element.hit_test(rect, Point::new(x, y))).collect()
+    };
+
+    // The corners of the rotated element are outside of it while its rotated
+    // corners stick out of its frame, the space after the elements is empty.
+    let mut row = Row::new((leaf(10.0, 20.0).0, rotated()));
+    place(&mut row, 60.0, 20.0);
+    let rect = Rect::new(0.0, 0.0, 60.0, 20.0);
+    assert_eq!(hits(&row, rect, &[(5.0, 5.0), (11.0, 1.0), (20.0, 1.0), (32.0, 10.0), (40.0, 10.0)]), [true, false, true, true, false]);
+    let moved = rect.offset(100.0, 100.0);
+    assert_eq!(hits(&row, moved, &[(111.0, 101.0), (120.0, 101.0)]), [false, true]);
+
+    let mut space = Space::all(rotated(), unsigned(5.0));
+    place(&mut space, 30.0, 30.0);
+    let rect = Rect::new(0.0, 0.0, 30.0, 30.0);
+    assert_eq!(hits(&space, rect, &[(1.0, 1.0), (6.0, 6.0), (15.0, 6.0), (15.0, 1.0)]), [false, false, true, true]);
+
+    let mut align = Align::center(rotated());
+    place(&mut align, 40.0, 40.0);
+    let rect = Rect::new(0.0, 0.0, 40.0, 40.0);
+    assert_eq!(hits(&align, rect, &[(11.0, 11.0), (20.0, 11.0), (2.0, 2.0)]), [false, true, false]);
+
+    // The stroke of a border is hit while its inside is left to the element.
+    let stroke = Stroke {
+        width: Length::Px(unsigned(2.0)),
+        shader: Shader::default(),
+        cap: Cap::Flat,
+        join: Join::Miter(unsigned(4.0)),
+    };
+    let framed = || Border::new(Space::all(leaf(4.0, 4.0).0, unsigned(2.0)), stroke.clone());
+    let mut border = framed();
+    place(&mut border, 12.0, 12.0);
+    let rect = Rect::new(0.0, 0.0, 12.0, 12.0);
+    assert_eq!(hits(&border, rect, &[(1.0, 1.0), (5.0, 5.0), (3.0, 3.0)]), [true, true, false]);
+
+    let mut stack = Stack::new((leaf(10.0, 10.0).0, framed())).position(
+        1,
+        Positioned {
+            right: Some(unsigned(0.0)),
+            bottom: Some(unsigned(0.0)),
+            ..Default::default()
+        },
+    );
+    place(&mut stack, 30.0, 30.0);
+    let rect = Rect::new(0.0, 0.0, 30.0, 30.0);
+    assert_eq!(hits(&stack, rect, &[(5.0, 5.0), (15.0, 15.0), (19.0, 19.0), (21.0, 21.0), (24.0, 24.0)]), [true, false, true, false, true]);
+}
+
+#[test]
+fn text_style() {
+    let large = Style { size: Positive::new(24.0).unwrap(), ..Style::default() };
+    let context = Context::default().with_text_style(large.clone());
+    assert_eq!(Length::Em(unsigned(0.5)).resolvef(&Context::default(), 0.0), 8.0);
+    assert_eq!(Length::Em(unsigned(0.5)).resolvef(&context, 0.0), 12.0);
+
+    // Without a registered font the text is as high as its size.
+    let (a, ra) = leaf(10.0, 10.0);
+    let mut column = TextStyle::new(Column::new((Space::all(a, Length::Em(unsigned(0.25))), Text::inherited("inherited"))), large.clone());
+    let size = column.layout(BoxConstraints::unbounded(), &Context::default());
+    assert_eq!(size.heightf(), 22.0 + 24.0);
+    assert_eq!(column.inner().children().1.style(), &large);
+    place(&mut column, 30.0, 46.0);
+    assert_eq!(ra.rect.get(), Rect::new(6.0, 6.0, 10.0, 10.0));
+
+    let mut own = TextStyle::new(Text::new("own", Style::default()), large);
+    own.layout(BoxConstraints::unbounded(), &Context::default());
+    assert_eq!(own.inner().style(), &Style::default());
+}
+
+#[test]
+fn ink_overflow() {
+    let insets = |top: f32, right: f32, bottom: f32, left: f32| Insets::trbl(unsigned(top), unsigned(right), unsigned(bottom), unsigned(left));
+    let halo = || Shadow::new(leaf(10.0, 10.0).0, [BoxShadow::new(Vector::new(0.0, 0.0), unsigned(0.0), Color::black()).spread(Number::new(4.0).unwrap())]);
+
+    // The shadow sticks out of the space around it by 2 on each side, the row
+    // starts with another element so the shadow only reaches past its end.
+    let mut row = Row::new((leaf(10.0, 14.0).0, Space::all(halo(), unsigned(2.0))));
+    place(&mut row, 24.0, 14.0);
+    assert_eq!(row.ink_overflow(), insets(2.0, 2.0, 2.0, 0.0));
+    place(&mut row, 30.0, 14.0);
+    assert_eq!(row.ink_overflow(), insets(2.0, 0.0, 2.0, 0.0));
+
+    let mut column = Column::new((Align::center(halo()),));
+    place(&mut column, 10.0, 20.0);
+    assert_eq!(column.ink_overflow(), insets(4.0, 4.0, 0.0, 4.0));
+    let mut stack = Stack::new((leaf(10.0, 10.0).0, Align::center(halo())));
+    place(&mut stack, 16.0, 10.0);
+    assert_eq!(stack.ink_overflow(), insets(4.0, 1.0, 4.0, 1.0));
+
+    // A rotated square reaches half of its diagonal from its center.
+    let mut rotated = Transform::new(leaf(20.0, 20.0).0, Affine::rotate(std::f32::consts::FRAC_PI_4));
+    place(&mut rotated, 20.0, 20.0);
+    let reach = 10.0 * std::f32::consts::SQRT_2 - 10.0;
+    let Insets { top, right, bottom, left } = rotated.ink_overflow();
+    for side in [top, right, bottom, left] {
+        assert!((f32::from(side) - reach).abs() < 1e-3);
+    }
+}
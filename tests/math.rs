@@ -0,0 +1,540 @@
+use haruhi::math::*;
+use proptest::prelude::*;
+
+fn number() -> impl Strategy<Value = Number> {
+    any::<f32>().prop_filter_map("not a number", |value| Number::new(value).ok())
+}
+
+fn unsigned() -> impl Strategy<Value = Unsigned> {
+    any::<f32>().prop_filter_map("not unsigned", |value| Unsigned::new(value).ok())
+}
+
+fn positive() -> impl Strategy<Value = Positive> {
+    any::<f32>().prop_filter_map("not positive", |value| Positive::new(value).ok())
+}
+
+fn percent_signed() -> impl Strategy<Value = PercentSigned> {
+    (-1.0f32..=1.0).prop_map(|value| PercentSigned::new(value).unwrap())
+}
+
+fn percent_unsigned() -> impl Strategy<Value = PercentUnsigned> {
+    (0.0f32..=1.0).prop_map(|value| PercentUnsigned::new(value).unwrap())
+}
+
+fn coordinate() -> impl Strategy<Value = f32> {
+    -1.0e6f32..1.0e6
+}
+
+fn length() -> impl Strategy<Value = Unsigned> {
+    (0.0f32..1.0e6).prop_map(|value| Unsigned::new(value).unwrap())
+}
+
+fn size() -> impl Strategy<Value = Size> {
+    (length(), length()).prop_map(|(width, height)| Size::new(width, height))
+}
+
+fn insets() -> impl Strategy<Value = Insets> {
+    (length(), length(), length(), length()).prop_map(|(top, right, bottom, left)| Insets::trbl(top, right, bottom, left))
+}
+
+fn vector() -> impl Strategy<Value = Vector> {
+    (coordinate(), coordinate()).prop_map(|(x, y)| Vector::new(x, y))
+}
+
+fn point() -> impl Strategy<Value = Point> {
+    (coordinate(), coordinate()).prop_map(|(x, y)| Point::new(x, y))
+}
+
+fn rect() -> impl Strategy<Value = Rect> {
+    (coordinate(), coordinate(), length(), length()).prop_map(|(x, y, width, height)| Rect::new(x, y, width.into(), height.into()))
+}
+
+/// Exact result if it is finite, the largest finite value of the same sign otherwise.
+fn saturated(value: f32) -> f32 {
+    value.clamp(-f32::MAX, f32::MAX)
+}
+
+proptest! {
+    #[test]
+    fn unsigned_add(a in unsigned(), b in unsigned()) {
+        let sum = a + b;
+        prop_assert!(Unsigned::new(sum.into()).is_ok());
+        prop_assert_eq!(f32::from(sum), saturated(f32::from(a) + f32::from(b)));
+        prop_assert_eq!(a.checked_add(b).is_some(), (f32::from(a) + f32::from(b)).is_finite());
+        prop_assert_eq!(a.saturating_add(b), sum);
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, sum);
+    }
+
+    #[test]
+    fn unsigned_sub(a in unsigned(), b in unsigned()) {
+        let difference = a - b;
+        prop_assert_eq!(f32::from(difference), f32::from(a) - f32::from(b));
+        prop_assert_eq!(a.checked_sub(b).is_some(), a >= b);
+        prop_assert_eq!(Unsigned::try_from(difference).ok(), a.checked_sub(b));
+        let saturating = a.saturating_sub(b);
+        prop_assert_eq!(f32::from(saturating), f32::from(difference).max(0.0));
+    }
+
+    #[test]
+    fn unsigned_mul(a in unsigned(), b in unsigned()) {
+        let product = a * b;
+        prop_assert!(Unsigned::new(product.into()).is_ok());
+        prop_assert_eq!(f32::from(product), saturated(f32::from(a) * f32::from(b)));
+        prop_assert_eq!(a.saturating_mul(b), product);
+        prop_assert_eq!(a.checked_mul(b).is_some(), (f32::from(a) * f32::from(b)).is_finite());
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, product);
+    }
+
+    #[test]
+    fn unsigned_div(a in unsigned(), b in unsigned()) {
+        prop_assume!(b != Unsigned::zero());
+        let quotient = a / b;
+        prop_assert!(Unsigned::new(quotient.into()).is_ok());
+        prop_assert_eq!(f32::from(quotient), saturated(f32::from(a) / f32::from(b)));
+        let mut assigned = a;
+        assigned /= b;
+        prop_assert_eq!(assigned, quotient);
+    }
+
+    #[test]
+    fn unsigned_checked_div(a in unsigned(), b in unsigned()) {
+        let expected = f32::from(a) / f32::from(b);
+        prop_assert_eq!(a.checked_div(b).map(f32::from), expected.is_finite().then_some(expected));
+        prop_assert_eq!(a.checked_rem(b).is_some(), b != Unsigned::zero());
+    }
+
+    #[test]
+    fn unsigned_rem(a in unsigned(), b in unsigned()) {
+        prop_assume!(b != Unsigned::zero());
+        let remainder = a % b;
+        prop_assert!(remainder < b);
+        prop_assert_eq!(f32::from(remainder), f32::from(a) % f32::from(b));
+        let mut assigned = a;
+        assigned %= b;
+        prop_assert_eq!(assigned, remainder);
+    }
+
+    #[test]
+    fn unsigned_neg(a in unsigned()) {
+        prop_assert_eq!(f32::from(-a), -f32::from(a));
+    }
+
+    #[test]
+    fn unsigned_positive(a in unsigned(), b in positive()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        prop_assert_eq!(f32::from(a + b), saturated(x + y));
+        prop_assert_eq!(f32::from(a - b), x - y);
+        prop_assert_eq!(f32::from(a * b), saturated(x * y));
+        prop_assert_eq!(f32::from(a / b), saturated(x / y));
+        prop_assert_eq!(f32::from(a % b), x % y);
+        prop_assert_eq!(a == b, x == y);
+        prop_assert_eq!(a.partial_cmp(&b), x.partial_cmp(&y));
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+        let mut assigned = a;
+        assigned /= b;
+        prop_assert_eq!(assigned, a / b);
+        let mut assigned = a;
+        assigned %= b;
+        prop_assert_eq!(assigned, a % b);
+    }
+
+    #[test]
+    fn unsigned_clamped(value in any::<f32>()) {
+        let clamped = Unsigned::clamped(value);
+        prop_assert!(Unsigned::new(clamped.into()).is_ok());
+        if let Ok(unsigned) = Unsigned::new(value) {
+            prop_assert_eq!(clamped, unsigned);
+        }
+    }
+
+    #[test]
+    fn number_arithmetic(a in number(), b in number()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        prop_assert_eq!(f32::from(-a), -x);
+        prop_assert_eq!(f32::from(a + b), saturated(x + y));
+        prop_assert_eq!(f32::from(a - b), saturated(x - y));
+        prop_assert_eq!(f32::from(a * b), saturated(x * y));
+        prop_assert_eq!(a.checked_add(b).is_some(), (x + y).is_finite());
+        prop_assert_eq!(a.checked_sub(b).is_some(), (x - y).is_finite());
+        prop_assert_eq!(a.checked_mul(b).is_some(), (x * y).is_finite());
+        prop_assert_eq!(a.checked_div(b).is_some(), (x / y).is_finite());
+        prop_assert_eq!(a.checked_rem(b).is_some(), y != 0.0);
+        if y != 0.0 {
+            prop_assert_eq!(f32::from(a / b), saturated(x / y));
+            prop_assert_eq!(f32::from(a % b), x % y);
+        }
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned -= b;
+        prop_assert_eq!(assigned, a - b);
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+        if y != 0.0 {
+            let mut assigned = a;
+            assigned /= b;
+            prop_assert_eq!(assigned, a / b);
+            let mut assigned = a;
+            assigned %= b;
+            prop_assert_eq!(assigned, a % b);
+        }
+    }
+
+    #[test]
+    fn number_unsigned(a in number(), b in unsigned()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        prop_assert_eq!(f32::from(a + b), saturated(x + y));
+        prop_assert_eq!(f32::from(a - b), saturated(x - y));
+        prop_assert_eq!(f32::from(a * b), saturated(x * y));
+        if y != 0.0 {
+            prop_assert_eq!(f32::from(a / b), saturated(x / y));
+            prop_assert_eq!(f32::from(a % b), x % y);
+        }
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned -= b;
+        prop_assert_eq!(assigned, a - b);
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+        if y != 0.0 {
+            let mut assigned = a;
+            assigned /= b;
+            prop_assert_eq!(assigned, a / b);
+            let mut assigned = a;
+            assigned %= b;
+            prop_assert_eq!(assigned, a % b);
+        }
+    }
+
+    #[test]
+    fn float_number(x in any::<f32>(), a in number()) {
+        let y = f32::from(a);
+        prop_assert_eq!((x + a).to_bits(), (x + y).to_bits());
+        prop_assert_eq!((x - a).to_bits(), (x - y).to_bits());
+        prop_assert_eq!((x * a).to_bits(), (x * y).to_bits());
+        prop_assert_eq!((x / a).to_bits(), (x / y).to_bits());
+        prop_assert_eq!((x % a).to_bits(), (x % y).to_bits());
+        prop_assert_eq!((a + x).to_bits(), (y + x).to_bits());
+        prop_assert_eq!((a - x).to_bits(), (y - x).to_bits());
+        prop_assert_eq!((a * x).to_bits(), (y * x).to_bits());
+        prop_assert_eq!((a / x).to_bits(), (y / x).to_bits());
+        prop_assert_eq!((a % x).to_bits(), (y % x).to_bits());
+        prop_assert_eq!(x == a, x == y);
+        prop_assert_eq!(x.partial_cmp(&a), x.partial_cmp(&y));
+
+        for (assigned, expected) in [
+            ({ let mut v = x; v += a; v }, x + y),
+            ({ let mut v = x; v -= a; v }, x - y),
+            ({ let mut v = x; v *= a; v }, x * y),
+            ({ let mut v = x; v /= a; v }, x / y),
+            ({ let mut v = x; v %= a; v }, x % y),
+        ] {
+            prop_assert_eq!(assigned.to_bits(), expected.to_bits());
+        }
+    }
+
+    #[test]
+    fn float_unsigned(x in any::<f32>(), a in unsigned()) {
+        let y = f32::from(a);
+        prop_assert_eq!((x + a).to_bits(), (x + y).to_bits());
+        prop_assert_eq!((x - a).to_bits(), (x - y).to_bits());
+        prop_assert_eq!((x * a).to_bits(), (x * y).to_bits());
+        prop_assert_eq!((x / a).to_bits(), (x / y).to_bits());
+        prop_assert_eq!((x % a).to_bits(), (x % y).to_bits());
+        prop_assert_eq!(x == a, x == y);
+        prop_assert_eq!(x.partial_cmp(&a), x.partial_cmp(&y));
+
+        for (assigned, expected) in [
+            ({ let mut v = x; v += a; v }, x + y),
+            ({ let mut v = x; v -= a; v }, x - y),
+            ({ let mut v = x; v *= a; v }, x * y),
+            ({ let mut v = x; v /= a; v }, x / y),
+            ({ let mut v = x; v %= a; v }, x % y),
+        ] {
+            prop_assert_eq!(assigned.to_bits(), expected.to_bits());
+        }
+    }
+
+    #[test]
+    fn positive_arithmetic(a in positive(), b in positive()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        for result in [a + b, a * b, a / b] {
+            prop_assert!(Positive::new(result.into()).is_ok());
+        }
+        prop_assert_eq!(f32::from(a + b), saturated(x + y));
+        prop_assert_eq!(f32::from(a - b), x - y);
+        prop_assert_eq!(a.checked_add(b).is_some(), (x + y).is_finite());
+        prop_assert_eq!(a.checked_sub(b).is_some(), a > b);
+        if (x * y).is_normal() {
+            prop_assert_eq!(f32::from(a * b), x * y);
+        }
+        if (x / y).is_normal() {
+            prop_assert_eq!(f32::from(a / b), x / y);
+        }
+        prop_assert_eq!(a.checked_mul(b).map(f32::from), (x * y > 0.0 && (x * y).is_finite()).then_some(x * y));
+        prop_assert_eq!(a.checked_div(b).map(f32::from), (x / y > 0.0 && (x / y).is_finite()).then_some(x / y));
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+        let mut assigned = a;
+        assigned /= b;
+        prop_assert_eq!(assigned, a / b);
+    }
+
+    #[test]
+    fn percent_signed_arithmetic(a in percent_signed(), b in percent_signed()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        prop_assert_eq!(f32::from(-a), -x);
+        prop_assert_eq!(f32::from(a * b), x * y);
+        prop_assert_eq!(a.checked_add(b).map(f32::from), (-1.0..=1.0).contains(&(x + y)).then_some(x + y));
+        prop_assert_eq!(a.checked_sub(b).map(f32::from), (-1.0..=1.0).contains(&(x - y)).then_some(x - y));
+        prop_assert_eq!(f32::from(a.saturating_add(b)), (x + y).clamp(-1.0, 1.0));
+        prop_assert_eq!(f32::from(a.saturating_sub(b)), (x - y).clamp(-1.0, 1.0));
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+    }
+
+    #[test]
+    fn percent_unsigned_arithmetic(a in percent_unsigned(), b in percent_unsigned()) {
+        let (x, y) = (f32::from(a), f32::from(b));
+        prop_assert_eq!(f32::from(a * b), x * y);
+        prop_assert_eq!(a.checked_add(b).map(f32::from), (x + y <= 1.0).then_some(x + y));
+        prop_assert_eq!(a.checked_sub(b).map(f32::from), (x - y >= 0.0).then_some(x - y));
+        prop_assert_eq!(f32::from(a.saturating_add(b)), (x + y).min(1.0));
+        prop_assert_eq!(f32::from(a.saturating_sub(b)), (x - y).max(0.0));
+        let mut assigned = a;
+        assigned *= b;
+        prop_assert_eq!(assigned, a * b);
+    }
+
+    #[test]
+    fn size_arithmetic(a in size(), b in size(), insets in insets(), factor in unsigned(), divisor in positive()) {
+        prop_assert_eq!(a + b, Size::new(a.width() + b.width(), a.height() + b.height()));
+        prop_assert_eq!(
+            a + insets,
+            Size::new(a.width() + insets.left + insets.right, a.height() + insets.top + insets.bottom)
+        );
+        prop_assert_eq!(a * factor, Size::new(a.width() * factor, a.height() * factor));
+        prop_assert_eq!(a / divisor, Size::new(a.width() / divisor, a.height() / divisor));
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned *= factor;
+        prop_assert_eq!(assigned, a * factor);
+        let mut assigned = a;
+        assigned /= divisor;
+        prop_assert_eq!(assigned, a / divisor);
+    }
+
+    #[test]
+    fn rect_arithmetic(rect in rect(), offset in vector(), insets in insets()) {
+        prop_assert_eq!(rect + offset, rect.offset(offset.x, offset.y));
+        prop_assert_eq!(rect - offset, rect.offset(-offset.x, -offset.y));
+        prop_assert_eq!(
+            rect + insets,
+            rect.inflate(insets.top.into(), insets.right.into(), insets.bottom.into(), insets.left.into())
+        );
+        let deflated = rect - insets;
+        prop_assert!(deflated.width >= 0.0 && deflated.height >= 0.0);
+        prop_assert_eq!(deflated.origin(), Point::new(rect.x + insets.left, rect.y + insets.top));
+
+        let mut assigned = rect;
+        assigned += offset;
+        prop_assert_eq!(assigned, rect + offset);
+        let mut assigned = rect;
+        assigned -= offset;
+        prop_assert_eq!(assigned, rect - offset);
+    }
+
+    #[test]
+    fn rect_geometry(a in rect(), b in rect(), point in point(), insets in insets()) {
+        prop_assert_eq!(a.intersection(&b), b.intersection(&a));
+        if let Some(shared) = a.intersection(&b) {
+            prop_assert!(!shared.is_empty());
+            prop_assert_eq!(shared.left(), a.left().max(b.left()));
+            prop_assert_eq!(shared.top(), a.top().max(b.top()));
+        }
+
+        let union = a.union(&b);
+        if !a.is_empty() && !b.is_empty() {
+            prop_assert_eq!(union.origin(), Point::new(a.x.min(b.x), a.y.min(b.y)));
+            prop_assert_eq!(union, b.union(&a));
+        }
+        let empty = Rect::new(point.x, point.y, 0.0, b.height);
+        prop_assert_eq!(a.union(&empty), a);
+        if !a.is_empty() {
+            prop_assert_eq!(empty.union(&a), a);
+        }
+
+        let bounds = Rect::bounding([a.origin(), point]).unwrap();
+        prop_assert_eq!(bounds, Rect::from_points(a.origin(), point));
+
+        prop_assert_eq!(a.contains_point(a.origin()), !a.is_empty());
+        prop_assert!(a.contains(&a));
+        let inflated = a + insets;
+        prop_assert!(inflated.width >= a.width && inflated.height >= a.height);
+        prop_assert_eq!(inflated.origin(), Point::new(a.x - insets.left, a.y - insets.top));
+    }
+
+    #[test]
+    fn point_arithmetic(a in point(), b in point(), offset in vector()) {
+        prop_assert_eq!(a + offset, Point::new(a.x + offset.x, a.y + offset.y));
+        prop_assert_eq!(a - offset, Point::new(a.x - offset.x, a.y - offset.y));
+        prop_assert_eq!(a - b, Vector::new(a.x - b.x, a.y - b.y));
+
+        let mut assigned = a;
+        assigned += offset;
+        prop_assert_eq!(assigned, a + offset);
+        let mut assigned = a;
+        assigned -= offset;
+        prop_assert_eq!(assigned, a - offset);
+    }
+
+    #[test]
+    fn vector_arithmetic(a in vector(), b in vector(), factor in coordinate()) {
+        prop_assert_eq!(a + b, Vector::new(a.x + b.x, a.y + b.y));
+        prop_assert_eq!(a - b, Vector::new(a.x - b.x, a.y - b.y));
+        prop_assert_eq!(a * factor, Vector::new(a.x * factor, a.y * factor));
+        prop_assert_eq!(-a, Vector::new(-a.x, -a.y));
+        if factor != 0.0 {
+            prop_assert_eq!(a / factor, Vector::new(a.x / factor, a.y / factor));
+        }
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned -= b;
+        prop_assert_eq!(assigned, a - b);
+        let mut assigned = a;
+        assigned *= factor;
+        prop_assert_eq!(assigned, a * factor);
+        if factor != 0.0 {
+            let mut assigned = a;
+            assigned /= factor;
+            prop_assert_eq!(assigned, a / factor);
+        }
+    }
+
+    #[test]
+    fn insets_arithmetic(a in insets(), b in insets(), factor in unsigned()) {
+        prop_assert_eq!(a + b, Insets::trbl(a.top + b.top, a.right + b.right, a.bottom + b.bottom, a.left + b.left));
+        prop_assert_eq!(a * factor, Insets::trbl(a.top * factor, a.right * factor, a.bottom * factor, a.left * factor));
+
+        let mut assigned = a;
+        assigned += b;
+        prop_assert_eq!(assigned, a + b);
+        let mut assigned = a;
+        assigned *= factor;
+        prop_assert_eq!(assigned, a * factor);
+    }
+}
+
+#[test]
+#[should_panic(expected = "divide by zero")]
+fn unsigned_div_by_zero() {
+    let _ = Unsigned::one() / Unsigned::zero();
+}
+
+#[test]
+#[should_panic(expected = "divide by zero")]
+fn unsigned_rem_by_zero() {
+    let _ = Unsigned::one() % Unsigned::zero();
+}
+
+#[test]
+#[should_panic(expected = "divide by zero")]
+fn number_div_by_zero() {
+    let _ = Number::one() / Number::zero();
+}
+
+#[test]
+#[should_panic(expected = "divide by zero")]
+fn number_rem_by_zero() {
+    let _ = Number::one() % Unsigned::zero();
+}
+
+#[test]
+fn overflow_saturates() {
+    let largest = Unsigned::largest();
+    assert_eq!(largest + largest, largest);
+    assert_eq!(largest * largest, largest);
+    assert_eq!(largest.checked_add(largest), None);
+    assert_eq!(f32::from(-largest - largest), -f32::MAX);
+    assert_eq!(largest / Positive::new(f32::MIN_POSITIVE).unwrap(), largest);
+}
+
+#[test]
+fn float_nan_is_unordered() {
+    for nan in [f32::NAN, -f32::NAN] {
+        for a in [Number::zero(), Number::one(), -Unsigned::largest()] {
+            assert_eq!(nan.partial_cmp(&a), None);
+            assert_eq!([nan < a, nan > a, nan <= a, nan >= a, nan == a], [false; 5]);
+        }
+        for a in [Unsigned::zero(), Unsigned::one(), Unsigned::largest()] {
+            assert_eq!(nan.partial_cmp(&a), None);
+            assert_eq!([nan < a, nan > a, nan <= a, nan >= a, nan == a], [false; 5]);
+        }
+    }
+}
+
+#[test]
+fn rect_geometry_cases() {
+    let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+    let b = Rect::new(5.0, 5.0, 10.0, 10.0);
+    assert_eq!(a.intersection(&b), Some(Rect::new(5.0, 5.0, 5.0, 5.0)));
+    assert_eq!(a.intersection(&Rect::new(10.0, 0.0, 5.0, 5.0)), None);
+    assert_eq!(a.union(&b), Rect::new(0.0, 0.0, 15.0, 15.0));
+
+    // Rectangles without area, such as points, add nothing to a union.
+    assert_eq!(a.union(&Rect::new(100.0, 100.0, 0.0, 0.0)), a);
+    assert_eq!(Rect::new(-5.0, -5.0, 0.0, 20.0).union(&a), a);
+    assert_eq!(Rect::default().union(&Rect::default()), Rect::default());
+
+    assert!(a.contains_point(Point::new(0.0, 0.0)));
+    assert!(a.contains_point(Point::new(9.5, 9.5)));
+    assert!(!a.contains_point(Point::new(10.0, 5.0)));
+    assert!(!a.contains_point(Point::new(5.0, -0.5)));
+    assert!(a.contains(&Rect::new(5.0, 5.0, 5.0, 5.0)));
+    assert!(a.contains(&a));
+    assert!(!a.contains(&b));
+
+    assert_eq!(a.inflate(1.0, 2.0, 3.0, 4.0), Rect::new(-4.0, -1.0, 16.0, 14.0));
+    assert_eq!(a.deflate(1.0, 2.0, 3.0, 4.0), Rect::new(4.0, 1.0, 4.0, 6.0));
+    assert_eq!(a.deflate(8.0, 8.0, 8.0, 8.0), Rect::new(8.0, 8.0, 0.0, 0.0));
+
+    let unsigned = |value| Unsigned::new(value).unwrap();
+    let insets = Insets::trbl(unsigned(1.0), unsigned(2.0), unsigned(3.0), unsigned(4.0));
+    assert_eq!(a + insets, Rect::new(-4.0, -1.0, 16.0, 14.0));
+    assert_eq!(a - insets, Rect::new(4.0, 1.0, 4.0, 6.0));
+    assert_eq!(a + Vector::new(1.0, -1.0), Rect::new(1.0, -1.0, 10.0, 10.0));
+    assert_eq!(a - Vector::new(1.0, -1.0), Rect::new(-1.0, 1.0, 10.0, 10.0));
+
+    assert_eq!(Rect::bounding([]), None);
+    assert_eq!(Rect::bounding([Point::new(3.0, 1.0), Point::new(-1.0, 4.0), Point::new(0.0, 0.0)]), Some(Rect::new(-1.0, 0.0, 4.0, 4.0)));
+    assert_eq!(Affine::scale(2.0, -1.0).transform_rect(&a), Rect::new(0.0, -10.0, 20.0, 10.0));
+}
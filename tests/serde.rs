@@ -0,0 +1,76 @@
+#![cfg(feature = "serde")]
+
+use haruhi::base::*;
+use haruhi::layout::Length;
+use haruhi::math::*;
+use haruhi::path::*;
+use haruhi::text::Style;
+
+fn error<T: serde::de::DeserializeOwned>(json: &str) -> String {
+    serde_json::from_str::<T>(json).err().unwrap().to_string()
+}
+
+#[test]
+fn numbers_are_validated() {
+    assert_eq!(serde_json::from_str::<Unsigned>("2.5").unwrap(), Unsigned::new(2.5).unwrap());
+    assert_eq!(serde_json::to_string(&Positive::new(0.5).unwrap()).unwrap(), "0.5");
+    assert_eq!(error::<Unsigned>("-1.0"), UnsignedError::Negative.to_string());
+    assert_eq!(error::<Positive>("0.0"), PositiveError::NegativeOrZero.to_string());
+    assert_eq!(error::<PercentSigned>("-1.5"), PercentSignedError::OutOfBounds.to_string());
+    assert_eq!(error::<PercentUnsigned>("1.5"), PercentUnsignedError::OutOfBounds.to_string());
+    assert_eq!(error::<Number>("1e39"), NumberError::Infinite.to_string());
+}
+
+#[test]
+fn color_round_trips() {
+    let color = Color {
+        red: PercentUnsigned::one(),
+        green: PercentUnsigned::new(0.5).unwrap(),
+        blue: PercentUnsigned::zero(),
+        alpha: PercentUnsigned::one(),
+    };
+    let json = serde_json::to_string(&color).unwrap();
+    assert_eq!(json, r#"{"red":1.0,"green":0.5,"blue":0.0,"alpha":1.0}"#);
+    assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+    assert!(error::<Color>(r#"{"red":2.0,"green":0.5,"blue":0.0,"alpha":1.0}"#).starts_with("value is out of bounds"));
+}
+
+#[test]
+fn stroke_and_style() {
+    let stroke: Stroke = serde_json::from_str(r#"{"width":{"Dp":2.0},"shader":{"Solid":{"red":0.0,"green":0.0,"blue":0.0,"alpha":1.0}},"cap":"Round","join":{"Miter":4.0}}"#).unwrap();
+    assert_eq!(stroke.width, Length::Dp(Unsigned::new(2.0).unwrap()));
+    assert_eq!(stroke.cap, Cap::Round);
+    assert_eq!(stroke.join, Join::Miter(Unsigned::new(4.0).unwrap()));
+
+    let style = r#"{
+        "shader": {"Linear": {"start": {"x": 0.0, "y": 0.0}, "end": {"x": 0.0, "y": 16.0}, "gradient": {"stops": [
+            {"position": 0.0, "color": {"red": 1.0, "green": 1.0, "blue": 1.0, "alpha": 1.0}},
+            {"position": 1.0, "color": {"red": 0.0, "green": 0.0, "blue": 0.0, "alpha": 1.0}}
+        ]}}},
+        "background": {"Solid": {"red": 0.0, "green": 0.0, "blue": 0.0, "alpha": 0.0}},
+        "font": "DejaVu Sans",
+        "family": "sans-serif",
+        "size": 16.0,
+        "weight": 400.0,
+        "underline": null,
+        "strikeout": 1.0,
+        "overline": null,
+        "italic": false,
+        "letter_spacing": 1.0,
+        "word_spacing": 1.0
+    }"#;
+    let style: Style = serde_json::from_str(style).unwrap();
+    assert_eq!(style.font, "DejaVu Sans");
+    assert_eq!(style.strikeout, Some(Positive::one()));
+    assert_eq!(style.background, Color::transparent().into());
+    let Shader::Linear { gradient, .. } = &style.shader else { panic!("shader is not linear") };
+    let gray = PercentUnsigned::new(0.5).unwrap();
+    assert_eq!(gradient.color_at(0.5), Color::new(gray, gray, gray, PercentUnsigned::one()));
+    assert_eq!(gradient.spread_mode(), Spread::Pad);
+    assert_eq!(error::<Gradient>(r#"{"stops": []}"#), GradientError::Empty.to_string());
+    let conic = r#"{"Conic": {"center": {"x": 0.0, "y": 0.0}, "angle": 1e39, "gradient": {"stops": [
+        {"position": 0.0, "color": {"red": 1.0, "green": 1.0, "blue": 1.0, "alpha": 1.0}}
+    ]}}}"#;
+    assert!(error::<Shader>(conic).starts_with(&NumberError::Infinite.to_string()));
+    assert!(error::<Style>(&serde_json::to_string(&style).unwrap().replace("16.0", "-16.0")).starts_with("value is negative or zero"));
+}
@@ -0,0 +1,326 @@
+use haruhi::base::*;
+use haruhi::blend::*;
+use haruhi::image::Image;
+use haruhi::layout::*;
+use haruhi::math::*;
+use haruhi::path::*;
+use haruhi::render::Canvas;
+use haruhi::render::snapshot::Snapshot;
+use haruhi::render::software::render;
+use haruhi::text::Style;
+use haruhi::transform::*;
+
+fn reference(name: &str) -> String {
+    format!("{}/tests/snapshots/{name}.png", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn unsigned(value: f32) -> Unsigned {
+    Unsigned::new(value).unwrap()
+}
+
+fn size(width: f32, height: f32) -> Size {
+    Size::new(unsigned(width), unsigned(height))
+}
+
+fn percent(value: f32) -> PercentUnsigned {
+    PercentUnsigned::new(value).unwrap()
+}
+
+fn rgba(red: f32, green: f32, blue: f32, alpha: f32) -> Color {
+    Color::new(percent(red), percent(green), percent(blue), percent(alpha))
+}
+
+fn boxed(size: f32) -> Border<Space<Blank>> {
+    let stroke = Stroke {
+        width: Length::Px(unsigned(2.0)),
+        shader: Shader::default(),
+        cap: Cap::Flat,
+        join: Join::Miter(unsigned(4.0)),
+    };
+    Border::new(Space::all(Blank, unsigned(size)), stroke)
+}
+
+#[test]
+fn border() {
+    Snapshot::new(size(16.0, 16.0)).assert_matches(&mut boxed(6.0), reference("border"));
+}
+
+#[test]
+fn border_scaled() {
+    Snapshot::new(size(16.0, 16.0))
+        .scale(Positive::new(2.0).unwrap())
+        .assert_matches(&mut boxed(6.0), reference("border_scaled"));
+}
+
+#[test]
+fn space() {
+    let mut element = Space::trbl(boxed(4.0), unsigned(1.0), unsigned(2.0), unsigned(3.0), unsigned(4.0));
+    Snapshot::new(size(18.0, 16.0)).assert_matches(&mut element, reference("space"));
+}
+
+#[test]
+fn align() {
+    Snapshot::new(size(24.0, 24.0)).assert_matches(&mut Align::bottom_right(boxed(3.0)), reference("align_bottom_right"));
+    Snapshot::new(size(24.0, 24.0)).assert_matches(&mut Align::center(boxed(3.0)), reference("align_center"));
+    Snapshot::new(size(24.0, 24.0)).assert_matches(&mut Align::top_left(boxed(3.0)), reference("align_top_left"));
+}
+
+#[test]
+fn overflow() {
+    let elements = || vec![Align::left(boxed(6.0)), Align::center(boxed(6.0)), Align::right(boxed(6.0))];
+    let mut stack = Array::new(elements(), Axis::Horizontal);
+    Snapshot::new(size(40.0, 16.0)).assert_matches(&mut stack, reference("overflow_stack"));
+    let mut crop = Array::new(elements(), Axis::Horizontal).overlap(OverlapPolicy::Crop);
+    Snapshot::new(size(40.0, 16.0)).assert_matches(&mut crop, reference("overflow_crop"));
+}
+
+#[test]
+fn grid() {
+    let elements = vec![boxed(1.0), boxed(2.0), boxed(1.0), boxed(1.0), boxed(2.0)];
+    let rows = vec![Track::Auto, Track::Flex(Flex::default())];
+    let columns = vec![Track::Fixed(unsigned(10.0)), Track::Auto, Track::Flex(Flex::default())];
+    let mut grid = Grid::new(elements, rows, columns)
+        .gap(unsigned(2.0), unsigned(4.0))
+        .cell(3, Cell::new(1, 0).span(1, 2))
+        .cell(4, Cell::new(1, 2).align(PercentSigned::zero(), PercentSigned::unit_pos()));
+    Snapshot::new(size(48.0, 32.0)).assert_matches(&mut grid, reference("grid"));
+}
+
+#[test]
+fn wrap() {
+    let elements: Vec<_> = [1.0, 3.0, 2.0, 1.0, 4.0, 1.0, 2.0].into_iter().map(boxed).collect();
+    let mut wrap = Wrap::new(elements, Axis::Horizontal)
+        .spacing(unsigned(2.0))
+        .run_spacing(unsigned(2.0))
+        .justify(Justify::SpaceBetween)
+        .cross_align(CrossAlign::Center);
+    Snapshot::new(size(48.0, 40.0)).assert_matches(&mut wrap, reference("wrap"));
+}
+
+#[test]
+fn stack() {
+    let badge = Positioned {
+        top: Some(unsigned(0.0)),
+        right: Some(unsigned(0.0)),
+        ..Default::default()
+    };
+    let bar = Positioned {
+        right: Some(unsigned(2.0)),
+        bottom: Some(unsigned(2.0)),
+        left: Some(unsigned(2.0)),
+        ..Default::default()
+    };
+    let mut stack = Stack::new(vec![boxed(6.0), boxed(2.0), boxed(1.0)])
+        .fit(StackFit::Expand)
+        .align(PercentSigned::zero(), PercentSigned::zero())
+        .position(1, badge)
+        .position(2, bar)
+        .z_index(0, 1);
+    Snapshot::new(size(32.0, 32.0)).assert_matches(&mut stack, reference("stack"));
+}
+
+#[test]
+fn sized() {
+    let half = percent(0.5);
+    let limits = BoxConstraints::new(12.0, 12.0, 0.0, 6.0).unwrap();
+    let elements = (
+        SizedBox::exact(boxed(0.0), size(8.0, 10.0)),
+        ConstrainedBox::new(boxed(6.0), limits),
+        AspectRatio::new(SizedBox::new(boxed(0.0), Some(Length::Px(unsigned(6.0))), None), Positive::new(0.5).unwrap()),
+        FractionallySized::new(boxed(0.0), None, Some(half)),
+    );
+    let mut row = Row::new(elements).justify(Justify::SpaceEvenly);
+    Snapshot::new(size(48.0, 24.0)).assert_matches(&mut row, reference("sized"));
+}
+
+#[test]
+fn right_to_left() {
+    let elements = || {
+        (
+            Space::start_end(boxed(2.0), unsigned(4.0), unsigned(0.0)),
+            boxed(4.0),
+            SizedBox::new(Align::top_start(boxed(1.0)), Some(Length::Px(unsigned(12.0))), None),
+        )
+    };
+    let mut ltr = Directionality::new(Row::new(elements()), Direction::LeftToRight);
+    Snapshot::new(size(40.0, 16.0)).assert_matches(&mut ltr, reference("left_to_right"));
+    let mut rtl = Directionality::new(Row::new(elements()), Direction::RightToLeft);
+    Snapshot::new(size(40.0, 16.0)).assert_matches(&mut rtl, reference("right_to_left"));
+}
+
+#[test]
+fn transform() {
+    let rotated = Transform::new(boxed(4.0), Affine::rotate(std::f32::consts::FRAC_PI_4));
+    let scaled = Transform::with_origin(boxed(4.0), Affine::scale(0.5, 0.5), PercentSigned::unit_neg(), PercentSigned::unit_neg());
+    let mut row = Row::new((rotated, scaled)).justify(Justify::SpaceEvenly);
+    Snapshot::new(size(40.0, 20.0)).assert_matches(&mut row, reference("transform"));
+}
+
+#[test]
+fn lengths() {
+    let half = percent(0.5);
+    let quarter = percent(0.25);
+    let hairline = Stroke {
+        width: Length::Dp(unsigned(1.0)),
+        shader: Shader::default(),
+        cap: Cap::Flat,
+        join: Join::Miter(unsigned(4.0)),
+    };
+    let elements = (
+        SizedBox::new(boxed(0.0), Some(Length::Px(unsigned(6.0))), Some(Length::Percent(half))),
+        TextStyle::new(Space::all(boxed(0.0), Length::Em(unsigned(0.5))), Style { size: Positive::new(8.0).unwrap(), ..Style::default() }),
+        Border::new(Space::vertical(Blank, Length::Percent(quarter), Length::Dp(unsigned(4.0))), hairline),
+    );
+    let mut row = Row::new(elements).justify(Justify::SpaceBetween).cross_align(CrossAlign::Start);
+    Snapshot::new(size(40.0, 16.0))
+        .scale(Positive::new(2.0).unwrap())
+        .assert_matches(&mut row, reference("lengths"));
+}
+
+/// Rectangle filled with the shader.
+struct Fill(Shader);
+
+impl Element for Fill {
+    fn layout(&mut self, constraints: BoxConstraints, _context: &Context) -> Size {
+        constraints.constrain(16.0, 16.0)
+    }
+
+    fn paint(&self, canvas: &mut dyn Canvas, rect: Rect) {
+        canvas.fill_rect(rect, &self.0);
+    }
+}
+
+#[test]
+fn shaders() {
+    let gradient = |spread| {
+        let stops = [
+            ColorStop::new(percent(0.0), rgba(1.0, 0.0, 0.0, 1.0)),
+            ColorStop::new(percent(0.5), rgba(0.0, 1.0, 0.0, 1.0)),
+            ColorStop::new(percent(1.0), Color::transparent()),
+        ];
+        Gradient::new(stops).unwrap().spread(spread)
+    };
+    let checkers = Image::from_rgba(2, 2, [[255, 255, 255, 255], [0, 0, 255, 255], [0, 0, 255, 255], [255, 255, 255, 255]].concat()).unwrap();
+
+    let fills = vec![
+        Fill(rgba(0.0, 0.0, 1.0, 1.0).into()),
+        Fill(Shader::Linear {
+            start: Point::new(16.0, 0.0),
+            end: Point::new(32.0, 0.0),
+            gradient: gradient(Spread::Pad),
+        }),
+        Fill(Shader::Linear {
+            start: Point::new(32.0, 0.0),
+            end: Point::new(40.0, 8.0),
+            gradient: gradient(Spread::Reflect),
+        }),
+        Fill(Shader::Radial {
+            center: Point::new(56.0, 8.0),
+            radius: Positive::new(4.0).unwrap(),
+            gradient: gradient(Spread::Repeat),
+        }),
+        Fill(Shader::Conic {
+            center: Point::new(72.0, 8.0),
+            angle: Number::zero(),
+            gradient: gradient(Spread::Pad),
+        }),
+        Fill(Shader::Pattern {
+            image: checkers,
+            rect: Rect::new(80.0, 0.0, 4.0, 4.0),
+        }),
+    ];
+    Snapshot::new(size(96.0, 16.0)).assert_matches(&mut Row::new(fills), reference("shaders"));
+}
+
+#[test]
+fn degenerate_patterns() {
+    let image = Image::from_rgba(1, 1, [255, 0, 0, 255]).unwrap();
+    for rect in [
+        Rect::new(0.0, 0.0, 0.0, 4.0),
+        Rect::new(0.0, 0.0, 4.0, 0.0),
+        Rect::new(0.0, 0.0, f32::MIN_POSITIVE, f32::MIN_POSITIVE),
+        Rect::new(f32::MAX, -f32::MAX, 4.0, 4.0),
+    ] {
+        let mut fill = Fill(Shader::Pattern { image: image.clone(), rect });
+        let framebuffer = render(&mut fill, size(4.0, 4.0), Positive::one());
+        let expected = if rect.is_empty() { [0, 0, 0, 0] } else { [255, 0, 0, 255] };
+        assert_eq!(framebuffer.pixel(1, 1), expected, "{rect:?}");
+    }
+}
+
+#[test]
+fn backgrounds() {
+    let gray = rgba(0.75, 0.75, 0.75, 1.0);
+    let card = Background::new(boxed(6.0).radii(Radii::all(unsigned(6.0))), gray).radii(Radii::all(unsigned(6.0)));
+
+    let gradient = Gradient::new([ColorStop::new(PercentUnsigned::zero(), Color::black()), ColorStop::new(PercentUnsigned::one(), gray)]).unwrap();
+    let shader = Shader::Linear { start: Point::new(20.0, 0.0), end: Point::new(40.0, 0.0), gradient };
+    let radii = Radii::new(size(12.0, 6.0), Size::zero(), size(4.0, 4.0), size(8.0, 20.0));
+    let pill = Background::new(Space::all(Blank, unsigned(8.0)), shader).radii(radii);
+
+    Snapshot::new(size(40.0, 16.0)).assert_matches(&mut Row::new((card, pill)), reference("backgrounds"));
+}
+
+#[test]
+fn shadows() {
+    let radii = Radii::all(unsigned(3.0));
+    let card = |shadows: Vec<BoxShadow>| {
+        let shadow = Shadow::new(Space::all(Blank, unsigned(6.0)), shadows).radii(radii);
+        Space::all(Background::new(shadow, Color::white()).radii(radii), unsigned(5.0))
+    };
+
+    let red = rgba(1.0, 0.0, 0.0, 1.0);
+    let outer = vec![
+        BoxShadow::new(Vector::new(0.0, 0.0), unsigned(0.0), red).spread(Number::new(1.0).unwrap()),
+        BoxShadow::new(Vector::new(1.0, 2.0), unsigned(2.0), Color::black().with_alpha(percent(0.5))),
+    ];
+    assert_eq!(Shadow::new(Blank, outer.clone()).ink_overflow(), Insets::trbl(unsigned(1.0), unsigned(4.0), unsigned(5.0), unsigned(2.0)));
+
+    let inset = vec![BoxShadow::new(Vector::new(1.0, 1.0), unsigned(2.0), Color::black()).spread(Number::new(1.0).unwrap()).inset(true)];
+    let mut element = Background::new(Row::new((card(outer), card(inset))), Color::white().darken(percent(0.1)));
+    Snapshot::new(size(44.0, 22.0)).assert_matches(&mut element, reference("shadows"));
+}
+
+#[test]
+fn blended_overflow() {
+    let red = rgba(1.0, 0.0, 0.0, 1.0);
+    let square = || Background::new(Space::all(Blank, unsigned(5.0)), rgba(0.0, 0.4, 1.0, 1.0));
+    let glowing = || Row::new((Shadow::new(square(), [BoxShadow::new(Vector::new(0.0, 0.0), unsigned(2.0), red).spread(Number::new(1.0).unwrap())]),));
+    let rotated = || Transform::new(square(), Affine::rotate(std::f32::consts::FRAC_PI_4));
+
+    // The shadows and the corners sticking out of the layers are composited,
+    // also by the modes limited to the ink of the element.
+    let mut element = Background::new(
+        Row::new((
+            Space::all(Blend::new(glowing(), BlendMode::Multiply), unsigned(7.0)),
+            Space::all(Blend::new(glowing(), BlendMode::SourceIn), unsigned(7.0)),
+            Space::all(Blend::new(rotated(), BlendMode::Multiply), unsigned(7.0)),
+            Space::all(Blend::new(rotated(), BlendMode::Source), unsigned(7.0)),
+        )),
+        Color::white().darken(percent(0.2)),
+    );
+    Snapshot::new(size(96.0, 24.0)).assert_matches(&mut element, reference("blended_overflow"));
+}
+
+#[test]
+fn layers() {
+    let square = || Background::new(Space::all(Blank, unsigned(5.0)), rgba(0.0, 0.4, 1.0, 1.0));
+    let circle = || Background::new(Space::all(Blank, unsigned(5.0)), rgba(1.0, 0.2, 0.0, 0.8)).radii(Radii::all(unsigned(5.0)));
+    let cell = |mode| {
+        let below = Space::trbl(square(), unsigned(0.0), unsigned(6.0), unsigned(6.0), unsigned(0.0));
+        let above = Space::trbl(Blend::new(circle(), mode), unsigned(6.0), unsigned(0.0), unsigned(0.0), unsigned(6.0));
+        Box::new(Stack::new((below, above))) as AnyElement
+    };
+
+    let overlapping = Stack::new((
+        Space::trbl(square(), unsigned(0.0), unsigned(6.0), unsigned(6.0), unsigned(0.0)),
+        Space::trbl(circle(), unsigned(6.0), unsigned(0.0), unsigned(0.0), unsigned(6.0)),
+    ));
+    let mut cells = vec![Box::new(Opacity::new(overlapping, percent(0.5))) as AnyElement];
+    cells.extend(
+        [BlendMode::Multiply, BlendMode::Screen, BlendMode::Overlay, BlendMode::Difference, BlendMode::DestinationOut, BlendMode::SourceIn]
+            .map(cell),
+    );
+    let mut element = Background::new(Row::new(cells), Color::white().darken(percent(0.2)));
+    Snapshot::new(size(112.0, 16.0)).assert_matches(&mut element, reference("layers"));
+}